@@ -1,417 +1,4217 @@
-use chrono::{Date, DateTime, TimeZone, Utc};
-use clap::Parser;
+use chrono::{DateTime, Datelike, Duration, Locale, NaiveDate, Utc};
+use clap::{App, ArgEnum, Args, FromArgMatches, Parser};
+use pulldown_cmark::{Event, Tag, TagEnd};
+use pure_rust_locales::locale_match;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
+use std::error::Error as _;
+use std::ffi::OsString;
 use std::fs::File;
-use std::path::Path;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Instant;
+use twitter_watch::data::{
+    self, parse_timestamp, record_line, timestamp_problem, Grouped, ReadOptions, Record,
+};
+use twitter_watch::text::{fit_graphemes, grapheme_len, heading_anchor, truncate_graphemes};
+use twitter_watch::thumbnails::{
+    https_profile_image_url, make_profile_image_thumbnail_url, profile_image_thumbnail_path,
+    rewrite_profile_image_host, ThumbnailDir,
+};
+use twitter_watch::{Error, ScreenNameRecord, SuspensionRecord};
 
-const REPORTED_LIMIT: usize = 7;
-const SCREEN_NAMES_FOLLOWERS_COUNT_LIMIT: usize = 200;
-const SUSPENSIONS_FOLLOWERS_COUNT_LIMIT: usize = 250;
 const HEADER_DATE_FORMAT: &str = "%e %B %Y";
+const HEADER_WEEK_FORMAT: &str = "Week %V of %G";
+const HEADER_MONTH_FORMAT: &str = "%B %Y";
+const EVENT_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
-fn main() -> Result<(), Error> {
+/// The exit code used when a report runs successfully but doesn't include any records.
+const EMPTY_REPORT_EXIT_CODE: i32 = 3;
+
+fn main() {
     let opts: Opts = Opts::parse();
+    init_logger(
+        opts.command.read_opts().progress
+            || opts
+                .command
+                .report_opts()
+                .is_some_and(|report| report.timing),
+    );
 
-    match opts.command {
-        Command::ScreenNames { base } => {
-            let base_path = Path::new(&base);
-            let mut data = csv::Reader::from_reader(File::open(base_path.join("data.csv"))?);
+    let started = Instant::now();
 
-            let mut by_date: HashMap<Date<Utc>, Vec<ScreenNameRecord>> = HashMap::new();
+    let result = run(opts);
+    log::info!("Finished in {:.2?}", started.elapsed());
 
-            for result in data.records() {
-                let record = ScreenNameRecord::try_from(result?)?;
-                let date = record.timestamp.date();
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(EMPTY_REPORT_EXIT_CODE),
+        Err(error) => {
+            eprintln!("Error: {}", error);
 
-                let records = by_date.entry(date).or_default();
-                records.push(record);
+            let mut source = error.source();
+            while let Some(cause) = source {
+                eprintln!("Caused by: {}", cause);
+                source = cause.source();
             }
 
-            let mut date_records = by_date
-                .into_iter()
-                .map(|(date, mut records)| {
-                    records.sort_by_key(|record| (Reverse(record.followers_count), record.user_id));
-                    (date, records)
-                })
-                .collect::<Vec<_>>();
+            std::process::exit(1);
+        }
+    }
+}
 
-            date_records.sort_by_key(|(date, _)| Reverse(*date));
-
-            println!("# Screen name changes");
-            println!("This report tracks screen name changes for several million accounts on Twitter, with a focus on far-right and far-right adjacent accounts");
-            println!("(including a lot of crypto / NFT shit, some spam, antivaxxers, etc.).\n");
-            println!("This page presents the last ten days of available data for all users with more than {} followers.", SCREEN_NAMES_FOLLOWERS_COUNT_LIMIT);
-            println!("Please note:");
-            println!("* The date listed indicates the day the change was detected, and in some cases it may have happened earlier.");
-            println!("* The \"Twitter ID\" column provides a stable link for the account in cases where the screen name has been changed again.");
-            println!("* Some accounts may have been suspended or deactivated since being added to the report.");
-            println!("* There's a lot of potentially offensive content here, including racial slurs and obscenity.\n");
-            println!("The full history of all detected changes for all tracked users is available in the [`data.csv`](./data.csv) file.");
-
-            println!("## Contents");
-
-            for (date, records) in date_records.iter().take(REPORTED_LIMIT) {
-                println!(
-                    "* [{} ({} changes found)](#{})",
-                    date.format(HEADER_DATE_FORMAT),
-                    records.len(),
-                    date.format(HEADER_DATE_FORMAT)
-                        .to_string()
-                        .trim()
-                        .replace(" ", "-")
-                );
-            }
+/// Log warnings (and informational messages such as progress and timings, if they were requested)
+/// to stderr unless `RUST_LOG` says otherwise, labelled like the error messages.
+fn init_logger(info: bool) {
+    let default_filter = if info { "info" } else { "warn" };
 
-            for (date, records) in date_records.into_iter().take(REPORTED_LIMIT) {
-                println!("\n## {}", date.format(HEADER_DATE_FORMAT));
-                println!(
-                    "Found {} screen name changes, with {} included here. ✔️ indicates that the account is verified and 🔒 that it is locked.",
-                    records.len(),
-                    records
-                        .iter()
-                        .filter(
-                            |record| record.followers_count >= SCREEN_NAMES_FOLLOWERS_COUNT_LIMIT
-                        )
-                        .count()
-                );
-                println!("<table>");
-                println!("<tr><th></th><th align=\"left\">Twitter ID</th><th align=\"left\">Previous screen name</th>");
-                println!("<th align=\"left\">New screen name</th><th align=\"left\">Status</th><th align=\"left\">Follower count</th></tr>");
-                for record in records.into_iter().take_while(|record| {
-                    record.followers_count >= SCREEN_NAMES_FOLLOWERS_COUNT_LIMIT
-                }) {
-                    let image_url =
-                        make_profile_image_thumbnail_url(&record.profile_image_url, &base_path);
-                    let img = format!(
-                        "<a href=\"{}\"><img src=\"{}\" width=\"40px\" height=\"40px\" align=\"center\"/></a>",
-                        record.profile_image_url, image_url
-                    );
-                    let id_link = format!(
-                        "<a href=\"https://twitter.com/intent/user?user_id={}\">{}</a>",
-                        record.user_id, record.user_id
-                    );
-                    let screen_name_link = format!(
-                        "<a href=\"https://twitter.com/{}\">{}</a>",
-                        record.new_screen_name, record.new_screen_name
-                    );
-                    let mut status = String::new();
-                    if record.protected {
-                        status.push_str("🔒");
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter))
+        .format(|out, record| {
+            let label = match record.level() {
+                log::Level::Error => "Error",
+                log::Level::Warn => "Warning",
+                log::Level::Info => "Info",
+                log::Level::Debug => "Debug",
+                log::Level::Trace => "Trace",
+            };
+
+            writeln!(out, "{}: {}", label, record.args())
+        })
+        .init();
+}
+
+/// Run the command, returning whether anything was reported (which is only ever false for the
+/// screen name and suspension reports).
+fn run(opts: Opts) -> Result<bool, Error> {
+    let mut reported_any = true;
+
+    match opts.command {
+        Command::ScreenNames(opts) => reported_any = screen_names_report(opts)?.count > 0,
+        Command::Suspensions(opts) => reported_any = suspensions_report(opts)?.count > 0,
+        Command::Search {
+            base,
+            kind,
+            user_id,
+            read,
+        } => {
+            let base_path = Path::new(base.as_deref().unwrap_or_else(|| kind.default_base()));
+            let data_file = data::data_file(base_path, false);
+
+            match kind {
+                Kind::ScreenNames => {
+                    let mut records =
+                        data::load::<ScreenNameRecord, _>(&data_file, &read.options(), Err)?;
+                    records.retain(|record| record.user_id == user_id);
+
+                    records.sort_by_key(|record| record.timestamp);
+
+                    if records.is_empty() {
+                        println!("No screen name changes found for user {}", user_id);
                     }
-                    if record.verified {
-                        status.push_str("✔️");
+
+                    for record in records {
+                        println!("{}", describe_screen_name_change(&record));
                     }
+                }
+                Kind::Suspensions => {
+                    let mut rows =
+                        data::load::<SuspensionRow, _>(&data_file, &read.options(), Err)?;
+                    // Rows without account details still match when they have the user ID.
+                    rows.retain(|row| match row {
+                        SuspensionRow::Suspension(record) => record.user_id == user_id,
+                        SuspensionRow::Unknown(unknown) => unknown.user_id == Some(user_id),
+                    });
 
-                    println!(
-                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td align=\"center\">{}</td><td>{}</td></tr>",
-                        img,
-                        id_link,
-                        record.previous_screen_name,
-                        screen_name_link,
-                        status,
-                        record.followers_count
-                    );
+                    rows.sort_by_key(|row| row.timestamp());
+
+                    if rows.is_empty() {
+                        println!("No suspensions found for user {}", user_id);
+                    }
+
+                    for row in rows {
+                        match row {
+                            SuspensionRow::Suspension(record) => {
+                                println!("{}", describe_suspension(&record))
+                            }
+                            SuspensionRow::Unknown(unknown) => {
+                                println!("{}", describe_unknown_suspension(&unknown))
+                            }
+                        }
+                    }
                 }
-                println!("</table>");
             }
         }
-        Command::Suspensions { base } => {
-            let base_path = Path::new(&base);
-            let mut data = csv::Reader::from_reader(File::open(base_path.join("data.csv"))?);
+        Command::Dashboard {
+            screen_names_base,
+            suspensions_base,
+            screen_names_min_followers,
+            suspensions_min_followers,
+            out_dir,
+            report,
+        } => {
+            // The reports would both write their day files and index to the same directory.
+            if report.split_by_day.is_some() {
+                return Err(Error::UnsupportedOption("--split-by-day"));
+            }
+
+            std::fs::create_dir_all(&out_dir)?;
+
+            let extension = report.format.extension();
+            let screen_names_file = format!("screen-names.{}", extension);
+            let suspensions_file = format!("suspensions.{}", extension);
+            let report_to = |file_name: &str| ReportOpts {
+                output: Some(out_dir.join(file_name).to_string_lossy().into_owned()),
+                ..report.clone()
+            };
 
-            let mut by_date: HashMap<Date<Utc>, Vec<Option<SuspensionRecord>>> = HashMap::new();
+            let screen_names = screen_names_report(ScreenNamesOpts {
+                base: screen_names_base,
+                min_followers: screen_names_min_followers,
+                report: report_to(&screen_names_file),
+                ..default_opts()
+            })?;
+            let suspensions = suspensions_report(SuspensionsOpts {
+                base: suspensions_base,
+                table_min_followers: suspensions_min_followers as u64,
+                count_min_followers: suspensions_min_followers as u64,
+                report: report_to(&suspensions_file),
+                ..default_opts()
+            })?;
 
-            for result in data.records() {
-                let csv_record = result?;
-                let (record, date) = if csv_record[3].is_empty() {
+            let index_path = out_dir.join("index.md");
+            write_output(Some(&index_path.to_string_lossy()), |out| {
+                writeln!(out, "# Twitter watch")?;
+                writeln!(out)?;
+                for (title, file_name, summary, noun) in [
                     (
-                        None,
-                        Utc.timestamp(csv_record[0].parse::<i64>().unwrap(), 0)
-                            .date(),
-                    )
-                } else {
-                    let record = SuspensionRecord::try_from(csv_record)?;
-                    let date = record.timestamp.date().clone();
-                    (Some(record), date)
-                };
+                        "Screen name changes",
+                        &screen_names_file,
+                        &screen_names,
+                        "changes",
+                    ),
+                    (
+                        "Suspensions",
+                        &suspensions_file,
+                        &suspensions,
+                        "suspensions",
+                    ),
+                ] {
+                    write!(
+                        out,
+                        "* [{}](./{}): {} {}",
+                        title,
+                        file_name,
+                        report.format_count(summary.count),
+                        noun
+                    )?;
+                    match summary.latest {
+                        Some(latest) => writeln!(
+                            out,
+                            ", most recently on {}",
+                            latest.format(HEADER_DATE_FORMAT).to_string().trim()
+                        )?,
+                        None => writeln!(out)?,
+                    }
+                }
+                Ok(())
+            })?;
+        }
+        Command::CheckThumbnails {
+            base,
+            kind,
+            min_followers,
+            report,
+        } => {
+            let base = base.unwrap_or_else(|| kind.default_base().to_string());
+            let base_path = Path::new(&base);
+            let thumbnails = report.thumbnail_dir(base_path);
+            let min_followers =
+                report.min_followers(min_followers.unwrap_or_else(|| kind.default_min_followers()));
 
-                let records = by_date.entry(date).or_default();
-                records.push(record);
+            let profile_image_urls = match kind {
+                Kind::ScreenNames => reported_profile_image_urls(
+                    &report.reported_days::<ScreenNameRecord, _>(&base, |record| {
+                        report.includes_account(record)
+                    })?,
+                    min_followers,
+                ),
+                Kind::Suspensions => reported_profile_image_urls(
+                    &report
+                        .reported_days::<SuspensionRow, _>(&base, |row| {
+                            matches!(row, SuspensionRow::Suspension(record) if report.includes_account(record))
+                        })?
+                        .into_iter()
+                        .map(|(date, rows)| {
+                            (
+                                date,
+                                rows.into_iter()
+                                    .filter_map(SuspensionRow::into_record)
+                                    .collect(),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                    min_followers,
+                ),
+            };
+
+            let mut seen = HashSet::new();
+            let mut missing_count = 0;
+
+            for profile_image_url in &profile_image_urls {
+                if let Some(path) = profile_image_thumbnail_path(profile_image_url, &thumbnails) {
+                    if seen.insert(path.clone()) && !path.exists() {
+                        println!("{} {}", path.display(), profile_image_url);
+                        missing_count += 1;
+                    }
+                }
             }
 
-            let mut date_records = by_date
-                .into_iter()
-                .map(|(date, records)| {
-                    let unknown_count = records
+            if missing_count > 0 {
+                log::warn!("{} of {} thumbnails missing", missing_count, seen.len());
+            } else {
+                log::info!("None of {} thumbnails missing", seen.len());
+            }
+        }
+        Command::Diff {
+            old,
+            new,
+            kind,
+            read,
+        } => match kind {
+            Kind::ScreenNames => {
+                let old_keys =
+                    data::load::<ScreenNameRecord, _>(Path::new(&old), &read.options(), Err)?
                         .iter()
-                        .filter(|maybe_record| maybe_record.is_none())
-                        .count();
+                        .map(screen_name_diff_key)
+                        .collect::<HashSet<_>>();
+                let date_records = added_records(
+                    data::load_grouped::<ScreenNameRecord, _>(
+                        Path::new(&new),
+                        &read.options(),
+                        Err,
+                    )?,
+                    |record| !old_keys.contains(&screen_name_diff_key(record)),
+                );
 
-                    let mut new_records = records
+                print_diff(&date_records, describe_screen_name_change);
+            }
+            Kind::Suspensions => {
+                let old_keys =
+                    data::load::<SuspensionRow, _>(Path::new(&old), &read.options(), Err)?
+                        .into_iter()
+                        .filter_map(SuspensionRow::into_record)
+                        .map(|record| suspension_diff_key(&record))
+                        .collect::<HashSet<_>>();
+                let date_records = added_records(
+                    data::load_grouped::<SuspensionRow, _>(Path::new(&new), &read.options(), Err)?
                         .into_iter()
-                        .filter_map(|maybe_record| maybe_record)
-                        .collect::<Vec<_>>();
+                        .map(|(date, rows)| {
+                            (
+                                date,
+                                rows.into_iter()
+                                    .filter_map(SuspensionRow::into_record)
+                                    .collect(),
+                            )
+                        })
+                        .collect(),
+                    |record| !old_keys.contains(&suspension_diff_key(record)),
+                );
+
+                print_diff(&date_records, describe_suspension);
+            }
+        },
+        Command::VerificationChanges { base, read } => {
+            let data_file = data::data_file(Path::new(&base), false);
+            let records = data::load::<ScreenNameRecord, _>(&data_file, &read.options(), Err)?;
 
-                    new_records
-                        .sort_by_key(|record| (Reverse(record.followers_count), record.user_id));
+            print_verification_changes(&verification_changes(&records));
+        }
+        Command::Summary {
+            screen_names_base,
+            suspensions_base,
+            read,
+        } => {
+            let screen_names = data::load::<ScreenNameRecord, _>(
+                &data::data_file(Path::new(&screen_names_base), false),
+                &read.options(),
+                Err,
+            )?;
+            let suspensions = data::load::<SuspensionRow, _>(
+                &data::data_file(Path::new(&suspensions_base), false),
+                &read.options(),
+                Err,
+            )?;
 
-                    (date, new_records, unknown_count)
+            let changed_user_count = screen_names
+                .iter()
+                .map(|record| record.user_id)
+                .collect::<HashSet<_>>()
+                .len();
+            let reversal_count = suspensions
+                .iter()
+                .filter(|row| {
+                    matches!(row, SuspensionRow::Suspension(record) if record.reversal.is_some())
                 })
-                .collect::<Vec<_>>();
+                .count();
+            let latest = screen_names
+                .iter()
+                .map(Record::timestamp)
+                .chain(suspensions.iter().map(Record::timestamp))
+                .max();
 
-            date_records.sort_by_key(|(date, _, _)| Reverse(*date));
-
-            println!("# Suspensions");
-            println!("This report tracks suspensions for about 20 million accounts on Twitter, with a focus on far-right and far-right adjacent accounts");
-            println!("(including a lot of crypto / NFT shit, some spam, antivaxxers, etc.).\n");
-            println!("This page presents the last week of available data for all users with more than {} followers.", SUSPENSIONS_FOLLOWERS_COUNT_LIMIT);
-            println!("Please note:");
-            println!("* The dates listed indicate when the suspension or reversal was detected, and in some cases it may have happened earlier.");
-            println!("* In some cases the screen name may have been changed before the account was suspended.");
-            println!("* There's a lot of potentially offensive content here, including racial slurs and obscenity.");
-            println!("* The presence of an account on this list does not indicate that we have identified it as a far-right account, just that it has connections to far-right networks.\n");
-
-            println!("The full history of all detected suspensions for all tracked users is available in the [`data.csv`](./data.csv) file.");
-
-            println!("## Contents");
-
-            for (date, records, unknown_count) in date_records.iter().take(REPORTED_LIMIT) {
-                println!(
-                    "* [{} ({} suspensions found)](#{})",
-                    date.format(HEADER_DATE_FORMAT),
-                    records.len() + unknown_count,
-                    date.format(HEADER_DATE_FORMAT)
-                        .to_string()
-                        .trim()
-                        .replace(" ", "-")
-                );
+            print!(
+                "{} tracked accounts have changed their screen names {} times, and there have been {} suspensions ({} reversed).",
+                group_digits(changed_user_count as u64, ","),
+                group_digits(screen_names.len() as u64, ","),
+                group_digits(suspensions.len() as u64, ","),
+                group_digits(reversal_count as u64, ",")
+            );
+
+            match latest {
+                Some(latest) => println!(
+                    " The most recent change was detected on {}.",
+                    latest.format(HEADER_DATE_FORMAT).to_string().trim()
+                ),
+                None => println!(),
             }
+        }
+        Command::Stats {
+            base,
+            kind,
+            min_followers,
+            read,
+        } => {
+            let base_path = Path::new(base.as_deref().unwrap_or_else(|| kind.default_base()));
+            let data_file = data::data_file(base_path, false);
+            let min_followers = min_followers.unwrap_or(0);
 
-            for (date, records, unknown_count) in date_records.into_iter().take(REPORTED_LIMIT) {
-                println!("\n## {}", date.format(HEADER_DATE_FORMAT));
-                println!(
-                    "Found {} suspensions, with {} included here. ✔️ indicates that the account was verified and 🔒 that it was locked.",
-                    records.len() + unknown_count,
-                    records
-                        .iter()
-                        .filter(|record| record.followers_count >= SUSPENSIONS_FOLLOWERS_COUNT_LIMIT)
-                        .count()
-                );
-                println!("<table>");
-                println!("<tr><th></th><th align=\"left\">Twitter ID</th><th align=\"left\">Screen name</th>");
-                println!("<th align=\"left\">Created</th><th align=\"left\">Reversed</th>");
-                println!(
-                    "<th align=\"left\">Status</th><th align=\"left\">Follower count</th></tr>"
-                );
-                for record in records.into_iter().take_while(|record| {
-                    record.followers_count >= SUSPENSIONS_FOLLOWERS_COUNT_LIMIT
-                }) {
-                    let image_url =
-                        make_profile_image_thumbnail_url(&record.profile_image_url, &base_path);
-                    let img = format!(
-                        "<a href=\"{}\"><img src=\"{}\" width=\"40px\" height=\"40px\" align=\"center\"/></a>",
-                        record.profile_image_url, image_url
-                    );
-                    let id_link = format!(
-                        "<a href=\"https://twitter.com/intent/user?user_id={}\">{}</a>",
-                        record.user_id, record.user_id
-                    );
-                    let screen_name_link = format!(
-                        "<a href=\"https://twitter.com/{}\">{}</a>",
-                        record.screen_name, record.screen_name
-                    );
+            match kind {
+                Kind::ScreenNames => {
+                    let mut records =
+                        data::load::<ScreenNameRecord, _>(&data_file, &read.options(), Err)?;
+                    records.retain(|record| record.followers_count >= min_followers);
 
-                    let created_at = record.created_at.format("%Y-%m-%d");
-                    let reversal = record
-                        .reversal
-                        .map(|value| format!("{}", value.format("%Y-%m-%d")))
-                        .unwrap_or_default();
+                    println!("## Screen name change statistics");
+                    print_account_stats(&records);
+                }
+                Kind::Suspensions => {
+                    let mut records = vec![];
+                    let mut unknown_count = 0;
 
-                    let mut status = String::new();
-                    if record.protected {
-                        status.push_str("🔒");
-                    }
-                    if record.verified {
-                        status.push_str("✔️");
+                    for row in data::load::<SuspensionRow, _>(&data_file, &read.options(), Err)? {
+                        match row {
+                            SuspensionRow::Suspension(record) => {
+                                if record.followers_count >= min_followers {
+                                    records.push(record);
+                                }
+                            }
+                            SuspensionRow::Unknown(_) => unknown_count += 1,
+                        }
                     }
 
+                    println!("## Suspension statistics");
+                    print_account_stats(&records);
+
+                    let reversed_count = records
+                        .iter()
+                        .filter(|record| record.reversal.is_some())
+                        .count();
+
                     println!(
-                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td align=\"center\">{}</td><td>{}</td></tr>",
-                        img,
-                        id_link,
-                        screen_name_link,
-                        created_at,
-                        reversal,
-                        status,
-                        record.followers_count
+                        "* Reversed: {} ({:.1}%)",
+                        reversed_count,
+                        percentage(reversed_count, records.len())
                     );
+
+                    // Rows without account details can't be filtered by follower count.
+                    if min_followers == 0 {
+                        println!("* Suspensions without account details: {}", unknown_count);
+                    }
                 }
-                println!("</table>");
             }
         }
     }
 
-    Ok(())
+    Ok(reported_any)
+}
+
+/// What a screen name or suspension report included.
+struct ReportSummary {
+    /// The number of records in the displayed days.
+    count: usize,
+    /// The most recent displayed day with any records.
+    latest: Option<NaiveDate>,
 }
 
-fn make_profile_image_thumbnail_url(profile_image_url: &str, base: &Path) -> String {
-    let re =
-        regex::Regex::new(r"^https?://([^/]+)/profile_images/(\d+)/(.*)_normal(\.[a-zA-Z0-9-]+)?$")
-            .unwrap();
+impl ReportSummary {
+    fn from_days(days: impl Iterator<Item = (NaiveDate, usize)>) -> Self {
+        let mut summary = Self {
+            count: 0,
+            latest: None,
+        };
+
+        for (date, count) in days {
+            summary.count += count;
+            if count > 0 && summary.latest.is_none_or(|latest| date > latest) {
+                summary.latest = Some(date);
+            }
+        }
+
+        summary
+    }
+}
 
-    re.captures(profile_image_url)
-        .and_then(|captures| {
-            let ((id, name), extension) = captures
-                .get(2)
-                .map(|m| m.as_str())
-                .zip(captures.get(3).map(|m| m.as_str()))
-                .zip(captures.get(4).map(|m| m.as_str()))?;
+fn screen_names_report(opts: ScreenNamesOpts) -> Result<ReportSummary, Error> {
+    let ScreenNamesOpts {
+        base,
+        min_followers,
+        report,
+        collapse,
+        show_deltas,
+        detect_reuse,
+        detect_reverts,
+        min_changes,
+        columns,
+        min_follower_growth,
+    } = opts;
+    let min_followers = report.min_followers(min_followers);
 
-            let path = format!("./thumbnails/{}-{}_400x400{}", id, name, extension);
+    report.check_format()?;
 
-            if base.join(&path).exists() {
-                Some(path)
-            } else {
+    let columns = ScreenNameColumn::resolve(columns, show_deltas, &report);
+    let show_deltas = columns.contains(&ScreenNameColumn::Change);
+
+    // Thumbnails are only looked up in the first base directory.
+    let base_path = Path::new(&base[0]);
+    let mut skipped = 0;
+    let mut timing = Timing::new(report.timing);
+
+    // Deltas, reuse, and revert detection need the full history, not only the reported days.
+    let mut date_records = report.load::<ScreenNameRecord, _>(
+        &base,
+        |record| report.includes_account(record),
+        show_deltas || min_follower_growth.is_some() || detect_reuse || detect_reverts,
+        &mut skipped,
+    )?;
+    let latest_date = date_records.iter().map(|(date, _)| *date).max();
+    timing.phase("read and parse");
+
+    // Deltas, reuse, and reverts are computed over the full dataset, not only the reported range
+    // (unless only the reported days have been loaded).
+    let deltas = if show_deltas || min_follower_growth.is_some() {
+        Some(follower_deltas(&date_records))
+    } else {
+        None
+    };
+    let reused = if detect_reuse {
+        Some(reused_screen_names(&date_records))
+    } else {
+        None
+    };
+    let reverted = if detect_reverts {
+        Some(reverted_screen_names(&date_records))
+    } else {
+        None
+    };
+
+    date_records.retain(|(date, _)| report.includes_date(*date));
+    let mut date_records = report.group_by.regroup(date_records);
+    report.sort_days(&mut date_records);
+
+    if let (Some(min_growth), Some(deltas)) = (min_follower_growth, &deltas) {
+        retain_follower_growth(&mut date_records, deltas, min_growth);
+    }
+    if min_changes > 1 {
+        retain_frequent_changers(&mut date_records, report.days, min_changes);
+    }
+
+    if collapse {
+        date_records = date_records
+            .into_iter()
+            .map(|(date, records)| (date, collapse_changes(records)))
+            .collect();
+    }
+    for (_, records) in &mut date_records {
+        report.sort_records(records);
+    }
+    timing.phase("group and sort");
+
+    let day_counts = date_records
+        .iter()
+        .take(report.days)
+        .map(|(date, records)| (*date, reported_records(records, min_followers).count()))
+        .collect::<Vec<_>>();
+    let summary = ReportSummary::from_days(day_counts.iter().copied());
+
+    if report.dry_run {
+        report.write_dry_run(&day_counts);
+        report_skipped(skipped);
+        timing.report();
+
+        return Ok(summary);
+    }
+
+    write_output(report.output_path()?.as_deref(), |out| {
+        match report.format {
+            Format::Markdown | Format::MarkdownTable | Format::Html => {
+                let thumbnails = report.thumbnail_dir(base_path);
+                let table = ScreenNamesTable {
+                    columns: &columns,
+                    thumbnails: &thumbnails,
+                    report: &report,
+                    deltas: deltas.as_ref(),
+                    reused: reused.as_ref(),
+                    reverted: reverted.as_ref(),
+                };
+                let write_markdown = |out: &mut dyn Write| {
+                    write_screen_names_markdown(out, date_records, min_followers, &table)?;
+                    report.write_footer(out, latest_date)
+                };
+
+                if report.format == Format::Html {
+                    write_html(
+                        out,
+                        "Screen name changes",
+                        report.followers_column(&columns, &ScreenNameColumn::Followers),
+                        write_markdown,
+                    )
+                } else {
+                    write_markdown(out)
+                }
+            }
+            Format::Json => write_screen_names_json(out, &date_records, min_followers, report.days),
+            Format::Rss => write_screen_names_rss(
+                out,
+                &date_records,
+                min_followers,
+                report.days,
+                report.base_url.as_deref(),
+            ),
+            Format::Csv => write_screen_names_csv(out, &date_records, min_followers, report.days),
+            Format::Text => write_text(
+                out,
+                date_records
+                    .iter()
+                    .take(report.days)
+                    .map(|(date, records)| (*date, records.as_slice())),
+                min_followers,
+                &report,
+            ),
+            Format::Buckets => write_buckets_csv(
+                out,
+                date_records
+                    .iter()
+                    .take(report.days)
+                    .map(|(date, records)| (*date, records.as_slice())),
+                &report.buckets,
+            ),
+        }
+    })?;
+
+    timing.phase("output");
+
+    report_skipped(skipped);
+    timing.report();
+
+    Ok(summary)
+}
+
+fn suspensions_report(opts: SuspensionsOpts) -> Result<ReportSummary, Error> {
+    let SuspensionsOpts {
+        base,
+        table_min_followers,
+        report,
+        show_unknown,
+        columns,
+        spike_threshold,
+        latest_only,
+        count_min_followers,
+        still_suspended_label,
+        max_account_age_days,
+    } = opts;
+    let min_followers = report.min_followers(table_min_followers as usize);
+    let count_min_followers = report.min_followers(count_min_followers as usize);
+
+    report.check_format()?;
+
+    let columns = SuspensionColumn::resolve(columns, &report);
+
+    // Thumbnails are only looked up in the first base directory.
+    let base_path = Path::new(&base[0]);
+    let mut skipped = 0;
+    let mut timing = Timing::new(report.timing);
+
+    let mut rows = report.load::<SuspensionRow, _>(
+        &base,
+        // Rows without account details can't be filtered by status, screen name, or age.
+        |row| match row {
+            SuspensionRow::Suspension(record) => {
+                report.includes_account(record)
+                    && max_account_age_days.is_none_or(|days| suspended_within(record, days))
+            }
+            SuspensionRow::Unknown(unknown) => {
+                unknown
+                    .user_id
+                    .map_or(report.watched_user_ids.is_none(), |user_id| {
+                        report.includes_user(user_id)
+                    })
+                    && !report.verified_only
+                    && !report.protected_only
+                    && report.screen_name_regex.is_none()
+                    && max_account_age_days.is_none()
+            }
+        },
+        // Spikes are found by comparing with the days before the reported ones.
+        spike_threshold.is_some(),
+        &mut skipped,
+    )?;
+    let latest_date = rows.iter().map(|(date, _)| *date).max();
+    timing.phase("read and parse");
+
+    // Spikes are found before the reported range is applied, so that the first reported days are
+    // also compared with the days before them.
+    let spikes = spike_threshold.map(|threshold| {
+        let mut period_counts = BTreeMap::new();
+        for (date, rows) in &rows {
+            *period_counts
+                .entry(report.group_by.period_start(*date))
+                .or_default() += rows.len();
+        }
+
+        suspension_spikes(
+            &period_counts.into_iter().rev().collect::<Vec<_>>(),
+            threshold,
+        )
+    });
+
+    rows.retain(|(date, _)| report.includes_date(*date));
+
+    let mut date_records = report
+        .group_by
+        .regroup(rows)
+        .into_iter()
+        .map(|(date, rows)| {
+            let mut records = vec![];
+            let mut unknowns = vec![];
+
+            for row in rows {
+                match row {
+                    SuspensionRow::Suspension(record) => records.push(record),
+                    SuspensionRow::Unknown(unknown) => unknowns.push(unknown),
+                }
+            }
+
+            (date, records, unknowns)
+        })
+        .collect::<Vec<_>>();
+
+    if latest_only {
+        retain_latest_suspensions(&mut date_records, report.days);
+    }
+    report.sort_days(&mut date_records);
+    for (_, records, _) in &mut date_records {
+        report.sort_records(records);
+    }
+    timing.phase("group and sort");
+
+    let day_counts = date_records
+        .iter()
+        .take(report.days)
+        .map(|(date, records, unknowns)| {
+            // Unknown suspensions are only counted when their tables are displayed.
+            let unknown_count = if show_unknown { unknowns.len() } else { 0 };
+            (
+                *date,
+                reported_records(records, min_followers).count() + unknown_count,
+            )
+        })
+        .collect::<Vec<_>>();
+    let summary = ReportSummary::from_days(day_counts.iter().copied());
+
+    if report.dry_run {
+        report.write_dry_run(&day_counts);
+        report_skipped(skipped);
+        timing.report();
+
+        return Ok(summary);
+    }
+
+    write_output(report.output_path()?.as_deref(), |out| {
+        match report.format {
+            Format::Markdown | Format::MarkdownTable | Format::Html => {
+                let thumbnails = report.thumbnail_dir(base_path);
+                let table = SuspensionsTable {
+                    columns: &columns,
+                    thumbnails: &thumbnails,
+                    report: &report,
+                    spikes: spikes.as_ref(),
+                    still_suspended_label: &still_suspended_label,
+                };
+                let write_markdown = |out: &mut dyn Write| {
+                    write_suspensions_markdown(
+                        out,
+                        date_records,
+                        min_followers,
+                        count_min_followers,
+                        &table,
+                        show_unknown,
+                    )?;
+                    report.write_footer(out, latest_date)
+                };
+
+                if report.format == Format::Html {
+                    write_html(
+                        out,
+                        "Suspensions",
+                        report.followers_column(&columns, &SuspensionColumn::Followers),
+                        write_markdown,
+                    )
+                } else {
+                    write_markdown(out)
+                }
+            }
+            Format::Json => write_suspensions_json(out, &date_records, min_followers, report.days),
+            Format::Rss => Err(Error::UnsupportedFormat("rss")),
+            Format::Csv => write_suspensions_csv(out, &date_records, min_followers, report.days),
+            Format::Text => write_text(
+                out,
+                date_records
+                    .iter()
+                    .take(report.days)
+                    .map(|(date, records, _)| (*date, records.as_slice())),
+                min_followers,
+                &report,
+            ),
+            Format::Buckets => write_buckets_csv(
+                out,
+                date_records
+                    .iter()
+                    .take(report.days)
+                    .map(|(date, records, _)| (*date, records.as_slice())),
+                &report.buckets,
+            ),
+        }
+    })?;
+
+    timing.phase("output");
+
+    report_skipped(skipped);
+    timing.report();
+
+    Ok(summary)
+}
+
+fn describe_screen_name_change(record: &ScreenNameRecord) -> String {
+    format!(
+        "{}: {} → {} ({} followers)",
+        record.timestamp.format(EVENT_TIMESTAMP_FORMAT),
+        record.previous_screen_name,
+        record.new_screen_name,
+        record.followers_count
+    )
+}
+
+fn describe_suspension(record: &SuspensionRecord) -> String {
+    let reversal = record
+        .reversal
+        .map(|value| format!(", reversed {}", value.format(EVENT_TIMESTAMP_FORMAT)))
+        .unwrap_or_default();
+
+    format!(
+        "{}: {} suspended ({} followers){}",
+        record.timestamp.format(EVENT_TIMESTAMP_FORMAT),
+        record.screen_name,
+        record.followers_count,
+        reversal
+    )
+}
+
+fn describe_unknown_suspension(unknown: &UnknownSuspension) -> String {
+    let reversal = unknown
+        .reversal
+        .map(|value| format!(", reversed {}", value.format(EVENT_TIMESTAMP_FORMAT)))
+        .unwrap_or_default();
+
+    format!(
+        "{}: suspended without account details{}",
+        unknown.timestamp.format(EVENT_TIMESTAMP_FORMAT),
+        reversal
+    )
+}
+
+/// The fields identifying a screen name change when comparing snapshots.
+fn screen_name_diff_key(record: &ScreenNameRecord) -> (u64, DateTime<Utc>, String) {
+    (
+        record.user_id,
+        record.timestamp,
+        record.new_screen_name.clone(),
+    )
+}
+
+/// The fields identifying a suspension when comparing snapshots.
+fn suspension_diff_key(record: &SuspensionRecord) -> (u64, DateTime<Utc>, Option<DateTime<Utc>>) {
+    (record.user_id, record.timestamp, record.reversal)
+}
+
+/// Keep only the records accepted by `is_added`, dropping days that end up empty.
+fn added_records<T, F: Fn(&T) -> bool>(date_records: Grouped<T>, is_added: F) -> Grouped<T> {
+    date_records
+        .into_iter()
+        .filter_map(|(date, mut records)| {
+            records.retain(|record| is_added(record));
+
+            if records.is_empty() {
                 None
+            } else {
+                Some((date, records))
             }
         })
-        .unwrap_or(profile_image_url.to_string())
+        .collect()
 }
 
-struct ScreenNameRecord {
-    timestamp: DateTime<Utc>,
-    user_id: u64,
-    verified: bool,
-    protected: bool,
-    followers_count: usize,
-    previous_screen_name: String,
-    new_screen_name: String,
-    profile_image_url: String,
+fn print_diff<T, F: Fn(&T) -> String>(date_records: &[(NaiveDate, Vec<T>)], describe: F) {
+    if date_records.is_empty() {
+        println!("No new records found");
+    }
+
+    for (date, records) in date_records {
+        println!("\n## {}", date.format(HEADER_DATE_FORMAT));
+
+        for record in records {
+            println!("* {}", describe(record));
+        }
+    }
 }
 
-impl TryFrom<csv::StringRecord> for ScreenNameRecord {
-    type Error = Error;
+/// Find the records where an account's verified status differs from its previous appearance in
+/// the data, most recent first.
+fn verification_changes(records: &[ScreenNameRecord]) -> Vec<&ScreenNameRecord> {
+    let mut history: HashMap<u64, Vec<&ScreenNameRecord>> = HashMap::new();
 
-    fn try_from(value: csv::StringRecord) -> Result<Self, Self::Error> {
-        if value.len() == 8 {
-            let ((((timestamp, user_id), verified), protected), followers_count) = value[0]
-                .parse::<i64>()
-                .map(|timestamp_s| Utc.timestamp(timestamp_s, 0))
-                .ok()
-                .zip(value[1].parse::<u64>().ok())
-                .zip(value[2].parse::<bool>().ok())
-                .zip(value[3].parse::<bool>().ok())
-                .zip(value[4].parse::<usize>().ok())
-                .ok_or_else(|| Error::InvalidScreenNamesRecord(value.clone()))?;
-
-            Ok(Self {
-                timestamp,
-                user_id,
-                verified,
-                protected,
-                followers_count,
-                previous_screen_name: value[5].to_string(),
-                new_screen_name: value[6].to_string(),
-                profile_image_url: value[7].to_string(),
-            })
-        } else {
-            Err(Error::InvalidScreenNamesRecord(value))
+    for record in records {
+        history.entry(record.user_id).or_default().push(record);
+    }
+
+    let mut changes = vec![];
+
+    for mut observations in history.into_values() {
+        observations.sort_by_key(|record| record.timestamp);
+
+        for pair in observations.windows(2) {
+            if pair[0].verified != pair[1].verified {
+                changes.push(pair[1]);
+            }
         }
     }
+
+    changes.sort_by_key(|record| (Reverse(record.timestamp), record.user_id));
+    changes
 }
 
-struct SuspensionRecord {
-    timestamp: DateTime<Utc>,
-    reversal: Option<DateTime<Utc>>,
-    user_id: u64,
-    created_at: DateTime<Utc>,
-    screen_name: String,
-    verified: bool,
-    protected: bool,
-    followers_count: usize,
-    profile_image_url: String,
+fn print_verification_changes(changes: &[&ScreenNameRecord]) {
+    if changes.is_empty() {
+        println!("No verification changes found");
+        return;
+    }
+
+    println!("| Detected | Twitter ID | Screen name | Change |");
+    println!("| --- | --- | --- | --- |");
+
+    for record in changes {
+        println!(
+            "| {} | [{}](https://twitter.com/intent/user?user_id={}) | {} | {} |",
+            record.timestamp.format("%Y-%m-%d"),
+            record.user_id,
+            record.user_id,
+            escape_markdown(&record.new_screen_name),
+            if record.verified {
+                "gained ✔️"
+            } else {
+                "lost ✔️"
+            }
+        );
+    }
 }
 
-impl TryFrom<csv::StringRecord> for SuspensionRecord {
-    type Error = Error;
+fn print_account_stats<A: Account>(records: &[A]) {
+    let user_ids = records
+        .iter()
+        .map(|record| record.user_id())
+        .collect::<HashSet<_>>();
+    let dates = records
+        .iter()
+        .map(|record| record.timestamp().date_naive())
+        .collect::<HashSet<_>>();
+    let mut followers_counts = records
+        .iter()
+        .map(|record| record.followers_count())
+        .collect::<Vec<_>>();
+    followers_counts.sort_unstable();
 
-    fn try_from(value: csv::StringRecord) -> Result<Self, Self::Error> {
-        if value.len() == 9 {
-            let (
-                (((((timestamp, reversal), user_id), created_at), verified), protected),
-                followers_count,
-            ) = value[0]
-                .parse::<i64>()
-                .map(|timestamp_s| Utc.timestamp(timestamp_s, 0))
-                .ok()
-                .zip(if value[1].is_empty() {
-                    Some(None)
-                } else {
-                    value[1]
-                        .parse::<i64>()
-                        .map(|timestamp_s| Some(Utc.timestamp(timestamp_s, 0)))
-                        .ok()
-                })
-                .zip(value[2].parse::<u64>().ok())
-                .zip(
-                    value[3]
-                        .parse::<i64>()
-                        .map(|timestamp_s| Utc.timestamp(timestamp_s, 0))
-                        .ok(),
-                )
-                .zip(value[5].parse::<bool>().ok())
-                .zip(value[6].parse::<bool>().ok())
-                .zip(value[7].parse::<usize>().ok())
-                .ok_or_else(|| Error::InvalidScreenNamesRecord(value.clone()))?;
+    println!("* Records: {}", records.len());
+    println!("* Distinct users: {}", user_ids.len());
+    println!("* Distinct days: {}", dates.len());
 
-            Ok(Self {
-                timestamp,
-                reversal,
-                user_id,
-                created_at,
-                screen_name: value[4].to_string(),
-                verified,
-                protected,
-                followers_count,
-                profile_image_url: value[8].to_string(),
-            })
+    if let Some((min, max)) = followers_counts.first().zip(followers_counts.last()) {
+        let middle = followers_counts.len() / 2;
+        let median = if followers_counts.len() % 2 == 0 {
+            (followers_counts[middle - 1] + followers_counts[middle]) / 2
         } else {
-            Err(Error::InvalidSuspensionsRecord(value))
-        }
+            followers_counts[middle]
+        };
+
+        println!(
+            "* Follower counts: minimum {}, median {}, maximum {}",
+            min, median, max
+        );
     }
+
+    let verified_count = records.iter().filter(|record| record.verified()).count();
+    let protected_count = records.iter().filter(|record| record.protected()).count();
+
+    println!(
+        "* Verified: {} ({:.1}%)",
+        verified_count,
+        percentage(verified_count, records.len())
+    );
+    println!(
+        "* Protected: {} ({:.1}%)",
+        protected_count,
+        percentage(protected_count, records.len())
+    );
 }
 
-#[derive(Debug, Parser)]
-#[clap(name = "report", version, author)]
-struct Opts {
-    #[clap(subcommand)]
-    command: Command,
+fn percentage(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
 }
 
-#[derive(Debug, Parser)]
-enum Command {
-    ScreenNames {
-        /// Screen name directory
-        #[clap(long, default_value = "screen-names/")]
-        base: String,
-    },
-    Suspensions {
-        /// Suspensions directory
-        #[clap(long, default_value = "suspensions/")]
-        base: String,
-    },
+/// Collapse multiple changes for the same account on a single day into one change.
+///
+/// The collapsed change goes from the earliest previous screen name to the latest new screen name,
+/// and otherwise uses the details from the most recent record.
+fn collapse_changes(mut records: Vec<ScreenNameRecord>) -> Vec<ScreenNameRecord> {
+    records.sort_by_key(|record| (record.user_id, record.timestamp));
+
+    let mut collapsed: Vec<ScreenNameRecord> = Vec::with_capacity(records.len());
+
+    for record in records {
+        match collapsed.last_mut() {
+            Some(last) if last.user_id == record.user_id => {
+                *last = ScreenNameRecord {
+                    previous_screen_name: std::mem::take(&mut last.previous_screen_name),
+                    ..record
+                };
+            }
+            _ => collapsed.push(record),
+        }
+    }
+
+    collapsed.sort_by_cached_key(|record| record.sort_key());
+    collapsed
+}
+
+/// Follower count changes keyed by user ID and detection time.
+type FollowerDeltas = HashMap<(u64, DateTime<Utc>), i64>;
+
+/// Compute the change in follower count since each account's previous appearance in the data.
+fn follower_deltas(date_records: &[(NaiveDate, Vec<ScreenNameRecord>)]) -> FollowerDeltas {
+    let mut history: HashMap<u64, Vec<(DateTime<Utc>, usize)>> = HashMap::new();
+
+    for record in date_records.iter().flat_map(|(_, records)| records) {
+        history
+            .entry(record.user_id)
+            .or_default()
+            .push((record.timestamp, record.followers_count));
+    }
+
+    let mut deltas = HashMap::new();
+
+    for (user_id, mut observations) in history {
+        observations.sort_unstable();
+
+        for pair in observations.windows(2) {
+            deltas.insert((user_id, pair[1].0), pair[1].1 as i64 - pair[0].1 as i64);
+        }
+    }
+
+    deltas
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum Error {
-    #[error("I/O error")]
-    Io(#[from] std::io::Error),
-    #[error("CSV error")]
-    Csv(#[from] csv::Error),
-    #[error("Invalid screen names record")]
-    InvalidScreenNamesRecord(csv::StringRecord),
-    #[error("Invalid suspensions record")]
-    InvalidSuspensionsRecord(csv::StringRecord),
+/// Screen name changes involved in the reuse of a screen name, keyed by user ID and detection time.
+type ReusedScreenNames = HashSet<(u64, DateTime<Utc>)>;
+
+/// Find screen name changes where one account took a screen name that a different account used
+/// earlier.
+///
+/// Both the earlier change to the screen name and the later change involving it are included.
+/// Screen names are compared case-insensitively, as they are on Twitter.
+fn reused_screen_names(date_records: &[(NaiveDate, Vec<ScreenNameRecord>)]) -> ReusedScreenNames {
+    let mut claims: HashMap<String, Vec<(u64, DateTime<Utc>)>> = HashMap::new();
+
+    for record in date_records.iter().flat_map(|(_, records)| records) {
+        claims
+            .entry(record.new_screen_name.to_lowercase())
+            .or_default()
+            .push((record.user_id, record.timestamp));
+    }
+
+    let mut reused = HashSet::new();
+
+    for record in date_records.iter().flat_map(|(_, records)| records) {
+        let names = [&record.previous_screen_name, &record.new_screen_name];
+
+        for name in names {
+            for (user_id, timestamp) in claims.get(&name.to_lowercase()).into_iter().flatten() {
+                if *user_id != record.user_id && *timestamp < record.timestamp {
+                    reused.insert((*user_id, *timestamp));
+                    reused.insert((record.user_id, record.timestamp));
+                }
+            }
+        }
+    }
+
+    reused
+}
+
+/// Keep only the changes for accounts whose follower count grew by more than `min_growth` percent
+/// since their previous appearance in the data.
+///
+/// Accounts without a previous appearance are dropped.
+fn retain_follower_growth(
+    date_records: &mut [(NaiveDate, Vec<ScreenNameRecord>)],
+    deltas: &FollowerDeltas,
+    min_growth: f64,
+) {
+    for (_, records) in date_records.iter_mut() {
+        records.retain(|record| {
+            deltas
+                .get(&(record.user_id, record.timestamp))
+                .is_some_and(|delta| {
+                    let previous = record.followers_count as i64 - delta;
+
+                    // Any growth from no followers at all counts as significant.
+                    if previous > 0 {
+                        *delta as f64 * 100.0 / previous as f64 > min_growth
+                    } else {
+                        *delta > 0
+                    }
+                })
+        });
+    }
+}
+
+/// Keep only the changes for accounts that changed their screen names at least `min_changes` times
+/// in the first `days` days.
+fn retain_frequent_changers(
+    date_records: &mut [(NaiveDate, Vec<ScreenNameRecord>)],
+    days: usize,
+    min_changes: usize,
+) {
+    let mut counts: HashMap<u64, usize> = HashMap::new();
+
+    for record in date_records
+        .iter()
+        .take(days)
+        .flat_map(|(_, records)| records)
+    {
+        *counts.entry(record.user_id).or_default() += 1;
+    }
+
+    for (_, records) in date_records.iter_mut() {
+        records.retain(|record| counts.get(&record.user_id).copied().unwrap_or(0) >= min_changes);
+    }
+}
+
+type RevertedScreenNames = HashSet<(u64, DateTime<Utc>)>;
+
+/// Find screen name changes where an account changed back to a screen name it used earlier.
+///
+/// Screen names are compared case-insensitively, as they are on Twitter.
+fn reverted_screen_names(
+    date_records: &[(NaiveDate, Vec<ScreenNameRecord>)],
+) -> RevertedScreenNames {
+    let mut by_user: HashMap<u64, Vec<&ScreenNameRecord>> = HashMap::new();
+
+    for record in date_records.iter().flat_map(|(_, records)| records) {
+        by_user.entry(record.user_id).or_default().push(record);
+    }
+
+    let mut reverted = HashSet::new();
+
+    for mut records in by_user.into_values() {
+        records.sort_by_key(|record| record.timestamp);
+
+        let mut held = HashSet::new();
+
+        for record in records {
+            let previous = record.previous_screen_name.to_lowercase();
+            let new = record.new_screen_name.to_lowercase();
+
+            if new != previous && held.contains(&new) {
+                reverted.insert((record.user_id, record.timestamp));
+            }
+
+            held.insert(previous);
+            held.insert(new);
+        }
+    }
+
+    reverted
+}
+
+/// Format a follower count change with an explicit sign and thousands separators.
+fn format_delta(delta: i64, separator: &str) -> String {
+    let sign = match delta.signum() {
+        1 => "+",
+        -1 => "-",
+        _ => "",
+    };
+
+    format!("{}{}", sign, group_digits(delta.unsigned_abs(), separator))
+}
+
+fn group_digits(value: u64, separator: &str) -> String {
+    let digits = value.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push_str(separator);
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+/// The records for a day that are included in a report.
+///
+/// This doesn't depend on the records being sorted by follower count.
+fn reported_records<A: Account>(
+    records: &[A],
+    min_followers: usize,
+) -> impl Iterator<Item = &A> + '_ {
+    records
+        .iter()
+        .filter(move |record| record.followers_count() >= min_followers)
+}
+
+/// The profile image URLs of the reported records on the given days, which are the images a report
+/// shows.
+fn reported_profile_image_urls<A: Account>(
+    date_records: &[(NaiveDate, Vec<A>)],
+    min_followers: usize,
+) -> Vec<String> {
+    date_records
+        .iter()
+        .flat_map(|(_, records)| reported_records(records, min_followers))
+        .map(|record| record.profile_image_url().to_string())
+        .collect()
+}
+
+/// The `count` reported records with the most followers across all of the given days, together
+/// with the day each was reported under.
+fn top_records<'a, A: Account>(
+    days: impl Iterator<Item = (NaiveDate, &'a [A])>,
+    min_followers: usize,
+    count: usize,
+) -> Vec<(NaiveDate, &'a A)> {
+    let mut records = days
+        .flat_map(|(date, records)| {
+            reported_records(records, min_followers).map(move |record| (date, record))
+        })
+        .collect::<Vec<_>>();
+
+    records.sort_by_key(|(_, record)| Reverse(record.followers_count()));
+    records.truncate(count);
+    records
+}
+
+fn write_screen_names_markdown(
+    out: &mut dyn Write,
+    date_records: Grouped<ScreenNameRecord>,
+    min_followers: usize,
+    table: &ScreenNamesTable,
+) -> Result<(), Error> {
+    let report = table.report;
+
+    // An empty report would otherwise have only the introduction and an empty contents list.
+    if date_records
+        .iter()
+        .take(report.days)
+        .all(|(_, records)| reported_records(records, min_followers).next().is_none())
+    {
+        writeln!(out, "No changes detected in the selected window.")?;
+        return Ok(());
+    }
+
+    if !report.quiet {
+        if let Some(header_file) = &report.header_file {
+            out.write_all(&std::fs::read(header_file)?)?;
+        } else {
+            writeln!(out, "# Screen name changes")?;
+            writeln!(out, "This report tracks screen name changes for several million accounts on Twitter, with a focus on far-right and far-right adjacent accounts")?;
+            writeln!(
+                out,
+                "(including a lot of crypto / NFT shit, some spam, antivaxxers, etc.).\n"
+            )?;
+            writeln!(
+                out,
+                "This page presents {} of available data for {}.",
+                report.reported_period(),
+                report.covered_users(min_followers)
+            )?;
+            writeln!(out, "Please note:")?;
+            writeln!(out, "* The date listed indicates the day the change was detected, and in some cases it may have happened earlier.")?;
+            writeln!(out, "* The \"Twitter ID\" column provides a stable link for the account in cases where the screen name has been changed again.")?;
+            writeln!(
+                out,
+                "* Some accounts may have been suspended or deactivated since being added to the report."
+            )?;
+            writeln!(out, "* There's a lot of potentially offensive content here, including racial slurs and obscenity.\n")?;
+            writeln!(out, "The full history of all detected changes for all tracked users is available in the [`data.csv`]({}) file.", report.absolute_link("./data.csv"))?;
+        }
+    }
+
+    if let Some(count) = report.top {
+        let top = top_records(
+            date_records
+                .iter()
+                .take(report.days)
+                .map(|(date, records)| (*date, records.as_slice())),
+            min_followers,
+            count,
+        );
+
+        writeln!(out, "\n## Top {} accounts", report.format_count(count))?;
+        writeln!(
+            out,
+            "The {} accounts with the most followers that changed their screen names in this period. ✔️ indicates that the account is verified and 🔒 that it is locked.{}",
+            report.format_count(top.len()),
+            table.marker_legend()
+        )?;
+        write_table_start(out, report)?;
+        table.write_header(out, true)?;
+        for (index, (date, record)) in top.into_iter().enumerate() {
+            table.write_row(out, record, index + 1, Some(date))?;
+        }
+        write_table_end(out, report)?;
+
+        return Ok(());
+    }
+
+    let displayed_counts = report.displayed_row_counts(
+        &date_records
+            .iter()
+            .take(report.days)
+            .map(|(_, records)| reported_records(records, min_followers).count())
+            .collect::<Vec<_>>(),
+    );
+
+    if !report.quiet || report.split_by_day.is_some() {
+        writeln!(out, "## Contents")?;
+
+        for ((date, records), _) in date_records
+            .iter()
+            .zip(&displayed_counts)
+            .filter(|(_, displayed_count)| displayed_count.is_some())
+        {
+            writeln!(
+                out,
+                "* [{} ({} changes found)]({})",
+                report.format_header(*date),
+                report.format_count(records.len()),
+                report.date_link(*date)
+            )?;
+        }
+    }
+
+    let mut omitted = (0, 0);
+    let latest_displayed = date_records
+        .iter()
+        .zip(&displayed_counts)
+        .filter_map(|((date, _), displayed_count)| displayed_count.map(|_| *date))
+        .max();
+
+    for ((date, records), displayed_count) in date_records.into_iter().zip(displayed_counts) {
+        let reported_count = reported_records(&records, min_followers).count();
+        let displayed_count = match displayed_count {
+            Some(displayed_count) => displayed_count,
+            None => {
+                omitted = (omitted.0 + 1, omitted.1 + reported_count);
+                continue;
+            }
+        };
+
+        report.write_day_section(out, date, |out| {
+            writeln!(out, "\n## {}", report.format_heading(date))?;
+            writeln!(
+                out,
+                "Found {} screen name changes, with {} included here. ✔️ indicates that the account is verified and 🔒 that it is locked.{}",
+                report.format_count(records.len()),
+                report.format_count(displayed_count),
+                table.marker_legend()
+            )?;
+            report.write_details_start(
+                out,
+                &format!(
+                    "{} ({} changes found)",
+                    report.format_header(date),
+                    report.format_count(records.len())
+                ),
+                Some(date) == latest_displayed,
+            )?;
+            write_table_start(out, report)?;
+            table.write_header(out, false)?;
+            for (index, record) in reported_records(&records, min_followers)
+                .take(displayed_count)
+                .enumerate()
+            {
+                table.write_row(out, record, index + 1, None)?;
+            }
+            write_table_end(out, report)?;
+            write_hidden_rows_note(out, reported_count - displayed_count, report)?;
+            report.write_details_end(out)
+        })?;
+    }
+
+    write_omitted_days_note(out, omitted, report)?;
+
+    Ok(())
+}
+
+/// A column of the screen name change tables.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ScreenNameColumn {
+    Image,
+    Id,
+    Old,
+    New,
+    Status,
+    Followers,
+    Change,
+    Archive,
+}
+
+impl ScreenNameColumn {
+    /// The columns to show, given the `--columns` option and the flags that add optional columns
+    /// to the default layout.
+    fn resolve(columns: Option<Vec<Self>>, show_deltas: bool, report: &ReportOpts) -> Vec<Self> {
+        let mut columns = columns.unwrap_or_else(|| {
+            let mut columns = vec![
+                Self::Image,
+                Self::Id,
+                Self::Old,
+                Self::New,
+                Self::Status,
+                Self::Followers,
+            ];
+            if show_deltas {
+                columns.push(Self::Change);
+            }
+            if report.archive_links {
+                columns.push(Self::Archive);
+            }
+            columns
+        });
+
+        // Redacted reports don't link to the accounts.
+        if report.redact {
+            columns.retain(|column| *column != Self::Archive);
+        }
+
+        columns
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            Self::Image => "",
+            Self::Id => "Twitter ID",
+            Self::Old => "Previous screen name",
+            Self::New => "New screen name",
+            Self::Status => "Status",
+            Self::Followers => "Follower count",
+            Self::Change => "Change",
+            Self::Archive => "Archive",
+        }
+    }
+
+    /// The alignment of the column's cells, when they aren't left-aligned.
+    fn align(self) -> Option<&'static str> {
+        match self {
+            Self::Status => Some("center"),
+            Self::Change => Some("right"),
+            _ => None,
+        }
+    }
+}
+
+/// The options for rendering screen name change tables.
+struct ScreenNamesTable<'a> {
+    columns: &'a [ScreenNameColumn],
+    thumbnails: &'a ThumbnailDir,
+    report: &'a ReportOpts,
+    deltas: Option<&'a FollowerDeltas>,
+    reused: Option<&'a ReusedScreenNames>,
+    reverted: Option<&'a RevertedScreenNames>,
+}
+
+impl ScreenNamesTable<'_> {
+    /// Explanations of the optional status markers, to follow the status legend in the text.
+    fn marker_legend(&self) -> String {
+        let mut legend = String::new();
+        if self.reused.is_some() {
+            legend.push_str(" 🔁 indicates that the screen name was also used by another account.");
+        }
+        if self.reverted.is_some() {
+            legend.push_str(
+                " ↩️ indicates that the account changed back to a screen name it used before.",
+            );
+        }
+        legend
+    }
+
+    fn write_header(&self, out: &mut dyn Write, detected: bool) -> Result<(), Error> {
+        let mut headings = vec![];
+        if self.report.show_rank {
+            headings.push(("#", Some("right")));
+        }
+        if detected {
+            headings.push(("Detected", None));
+        }
+        headings.extend(
+            self.columns
+                .iter()
+                .map(|column| (column.header(), column.align())),
+        );
+
+        write_table_header(out, &headings, self.report)
+    }
+
+    /// Write a table row, starting with its (one-based) rank in the table if requested and the
+    /// date the change was reported under if given.
+    fn write_row(
+        &self,
+        out: &mut dyn Write,
+        record: &ScreenNameRecord,
+        rank: usize,
+        detected: Option<NaiveDate>,
+    ) -> Result<(), Error> {
+        let report = self.report;
+
+        let mut cells = vec![];
+        if report.show_rank {
+            cells.push((rank.to_string(), Some("right")));
+        }
+        if let Some(date) = detected {
+            cells.push((date.format("%Y-%m-%d").to_string(), None));
+        }
+        for column in self.columns {
+            let cell = match column {
+                ScreenNameColumn::Image => {
+                    profile_image(&record.profile_image_url, self.thumbnails, report)
+                }
+                ScreenNameColumn::Id => user_id_link(record.user_id, report),
+                ScreenNameColumn::Old => screen_name_text(&record.previous_screen_name, report),
+                ScreenNameColumn::New => screen_name_link(&record.new_screen_name, report),
+                ScreenNameColumn::Status => {
+                    let mut status = String::new();
+                    if record.protected {
+                        status.push('🔒');
+                    }
+                    if record.verified {
+                        status.push_str("✔️");
+                    }
+                    if self
+                        .reused
+                        .is_some_and(|reused| reused.contains(&(record.user_id, record.timestamp)))
+                    {
+                        status.push('🔁');
+                    }
+                    if self.reverted.is_some_and(|reverted| {
+                        reverted.contains(&(record.user_id, record.timestamp))
+                    }) {
+                        status.push_str("↩️");
+                    }
+                    status
+                }
+                ScreenNameColumn::Followers => report.format_count(record.followers_count),
+                ScreenNameColumn::Change => self
+                    .deltas
+                    .and_then(|deltas| deltas.get(&(record.user_id, record.timestamp)))
+                    .map(|delta| format_delta(*delta, report.thousands_separator().unwrap_or(",")))
+                    .unwrap_or_default(),
+                ScreenNameColumn::Archive => archive_link(&record.new_screen_name, report),
+            };
+            cells.push((cell, column.align()));
+        }
+
+        write_table_row(out, &cells, report)
+    }
+}
+
+fn write_screen_names_json(
+    out: &mut dyn Write,
+    date_records: &[(NaiveDate, Vec<ScreenNameRecord>)],
+    min_followers: usize,
+    days: usize,
+) -> Result<(), Error> {
+    let records = date_records
+        .iter()
+        .take(days)
+        .flat_map(|(_, records)| reported_records(records, min_followers))
+        .collect::<Vec<_>>();
+
+    serde_json::to_writer(&mut *out, &records)?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn write_screen_names_rss(
+    out: &mut dyn Write,
+    date_records: &[(NaiveDate, Vec<ScreenNameRecord>)],
+    min_followers: usize,
+    days: usize,
+    base_url: Option<&str>,
+) -> Result<(), Error> {
+    let mut records = date_records
+        .iter()
+        .take(days)
+        .flat_map(|(_, records)| reported_records(records, min_followers))
+        .collect::<Vec<_>>();
+
+    records.sort_by_key(|record| Reverse(record.timestamp));
+
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(out, "<rss version=\"2.0\">")?;
+    writeln!(out, "<channel>")?;
+    writeln!(out, "<title>Screen name changes</title>")?;
+    writeln!(
+        out,
+        "<link>{}</link>",
+        base_url.unwrap_or(env!("CARGO_PKG_REPOSITORY"))
+    )?;
+    writeln!(
+        out,
+        "<description>Screen name changes for tracked accounts with at least {} followers</description>",
+        min_followers
+    )?;
+
+    for record in records {
+        writeln!(out, "<item>")?;
+        writeln!(
+            out,
+            "<title>@{} → @{}</title>",
+            escape_markup(&record.previous_screen_name),
+            escape_markup(&record.new_screen_name)
+        )?;
+        writeln!(
+            out,
+            "<link>https://twitter.com/intent/user?user_id={}</link>",
+            record.user_id
+        )?;
+        writeln!(
+            out,
+            "<guid isPermaLink=\"false\">{}-{}</guid>",
+            record.user_id,
+            record.timestamp.timestamp()
+        )?;
+        writeln!(out, "<pubDate>{}</pubDate>", record.timestamp.to_rfc2822())?;
+        writeln!(
+            out,
+            "<description>{} followers</description>",
+            record.followers_count
+        )?;
+        writeln!(out, "</item>")?;
+    }
+
+    writeln!(out, "</channel>")?;
+    writeln!(out, "</rss>")?;
+
+    Ok(())
+}
+
+/// Whether the account was suspended less than `days` days after it was created.
+///
+/// Accounts with a creation time after the suspension (which indicates bad data) are never
+/// included.
+fn suspended_within(record: &SuspensionRecord, days: u32) -> bool {
+    let age = record.timestamp - record.created_at;
+
+    age >= Duration::zero() && age < Duration::days(days.into())
+}
+
+/// Keep only the most recent suspension in the first `days` days for each account.
+///
+/// Suspensions without a user ID are always kept.
+fn retain_latest_suspensions(date_records: &mut [SuspensionsDay], days: usize) {
+    let mut latest: HashMap<u64, DateTime<Utc>> = HashMap::new();
+
+    for (_, records, unknowns) in date_records.iter().take(days) {
+        let suspensions = records
+            .iter()
+            .map(|record| (record.user_id, record.timestamp))
+            .chain(
+                unknowns
+                    .iter()
+                    .filter_map(|unknown| Some((unknown.user_id?, unknown.timestamp))),
+            );
+
+        for (user_id, timestamp) in suspensions {
+            let entry = latest.entry(user_id).or_insert(timestamp);
+            *entry = (*entry).max(timestamp);
+        }
+    }
+
+    for (_, records, unknowns) in date_records.iter_mut().take(days) {
+        records.retain(|record| latest.get(&record.user_id) == Some(&record.timestamp));
+        unknowns.retain(|unknown| {
+            unknown
+                .user_id
+                .is_none_or(|user_id| latest.get(&user_id) == Some(&unknown.timestamp))
+        });
+    }
+}
+
+/// The number of earlier days whose average suspension count a day is compared with to find spikes.
+const SPIKE_WINDOW: usize = 7;
+
+/// The days with more suspensions than `threshold` times the average of the previous
+/// [`SPIKE_WINDOW`] days in the data, given the number of suspensions on each day (most recent
+/// first).
+fn suspension_spikes(day_counts: &[(NaiveDate, usize)], threshold: f64) -> HashSet<NaiveDate> {
+    day_counts
+        .iter()
+        .enumerate()
+        .filter(|(index, (_, count))| {
+            let previous = &day_counts[index + 1..];
+            let previous = &previous[..previous.len().min(SPIKE_WINDOW)];
+            let total = previous.iter().map(|(_, count)| count).sum::<usize>();
+
+            total > 0 && *count as f64 > threshold * total as f64 / previous.len() as f64
+        })
+        .map(|(_, (date, _))| *date)
+        .collect()
+}
+
+fn write_suspensions_markdown(
+    out: &mut dyn Write,
+    date_records: Vec<SuspensionsDay>,
+    min_followers: usize,
+    count_min_followers: usize,
+    table: &SuspensionsTable,
+    show_unknown: bool,
+) -> Result<(), Error> {
+    let report = table.report;
+
+    // An empty report would otherwise have only the introduction and an empty contents list.
+    if date_records
+        .iter()
+        .take(report.days)
+        .all(|(_, records, unknowns)| {
+            reported_records(records, min_followers).next().is_none()
+                && (!show_unknown || unknowns.is_empty())
+        })
+    {
+        writeln!(out, "No suspensions detected in the selected window.")?;
+        return Ok(());
+    }
+
+    if !report.quiet {
+        if let Some(header_file) = &report.header_file {
+            out.write_all(&std::fs::read(header_file)?)?;
+        } else {
+            writeln!(out, "# Suspensions")?;
+            writeln!(out, "This report tracks suspensions for about 20 million accounts on Twitter, with a focus on far-right and far-right adjacent accounts")?;
+            writeln!(
+                out,
+                "(including a lot of crypto / NFT shit, some spam, antivaxxers, etc.).\n"
+            )?;
+            writeln!(
+                out,
+                "This page presents {} of available data for {}.",
+                report.reported_period(),
+                report.covered_users(min_followers)
+            )?;
+            writeln!(out, "Please note:")?;
+            writeln!(out, "* The dates listed indicate when the suspension or reversal was detected, and in some cases it may have happened earlier.")?;
+            writeln!(
+                out,
+                "* In some cases the screen name may have been changed before the account was suspended."
+            )?;
+            writeln!(out, "* There's a lot of potentially offensive content here, including racial slurs and obscenity.")?;
+            writeln!(out, "* The presence of an account on this list does not indicate that we have identified it as a far-right account, just that it has connections to far-right networks.\n")?;
+
+            writeln!(out, "The full history of all detected suspensions for all tracked users is available in the [`data.csv`]({}) file.", report.absolute_link("./data.csv"))?;
+        }
+    }
+
+    if let Some(count) = report.top {
+        let top = top_records(
+            date_records
+                .iter()
+                .take(report.days)
+                .map(|(date, records, _)| (*date, records.as_slice())),
+            min_followers,
+            count,
+        );
+
+        writeln!(out, "\n## Top {} accounts", report.format_count(count))?;
+        writeln!(
+            out,
+            "The {} accounts with the most followers that were suspended in this period. ✔️ indicates that the account was verified and 🔒 that it was locked.",
+            report.format_count(top.len())
+        )?;
+        write_table_start(out, report)?;
+        table.write_header(out, true)?;
+        for (index, (date, record)) in top.into_iter().enumerate() {
+            table.write_row(out, record, index + 1, Some(date))?;
+        }
+        write_table_end(out, report)?;
+
+        return Ok(());
+    }
+
+    let displayed_counts = report.displayed_row_counts(
+        &date_records
+            .iter()
+            .take(report.days)
+            .map(|(_, records, _)| reported_records(records, min_followers).count())
+            .collect::<Vec<_>>(),
+    );
+
+    if !report.quiet || report.split_by_day.is_some() {
+        writeln!(out, "## Contents")?;
+
+        for ((date, records, unknowns), _) in date_records
+            .iter()
+            .zip(&displayed_counts)
+            .filter(|(_, displayed_count)| displayed_count.is_some())
+        {
+            writeln!(
+                out,
+                "* [{} ({} suspensions found){}]({})",
+                report.format_header(*date),
+                report.format_count(records.len() + unknowns.len()),
+                table.spike_marker(*date),
+                report.date_link(*date)
+            )?;
+        }
+    }
+
+    let mut omitted = (0, 0);
+
+    let latest_displayed = date_records
+        .iter()
+        .zip(&displayed_counts)
+        .filter_map(|((date, _, _), displayed_count)| displayed_count.map(|_| *date))
+        .max();
+
+    for ((date, records, unknowns), displayed_count) in
+        date_records.into_iter().zip(displayed_counts)
+    {
+        let reported_count = reported_records(&records, min_followers).count();
+        let displayed_count = match displayed_count {
+            Some(displayed_count) => displayed_count,
+            None => {
+                omitted = (omitted.0 + 1, omitted.1 + reported_count);
+                continue;
+            }
+        };
+
+        report.write_day_section(out, date, |out| {
+            writeln!(out, "\n## {}", report.format_heading(date))?;
+            if table.is_spike(date) {
+                write!(out, "📈 ")?;
+            }
+            let included_count = records
+                .iter()
+                .filter(|record| record.followers_count >= count_min_followers)
+                .count();
+            writeln!(
+                out,
+                "Found {} suspensions, with {} included here. ✔️ indicates that the account was verified and 🔒 that it was locked.",
+                report.format_count(records.len() + unknowns.len()),
+                report.format_count(included_count)
+            )?;
+            report.write_details_start(
+                out,
+                &format!(
+                    "{} ({} suspensions found)",
+                    report.format_header(date),
+                    report.format_count(records.len() + unknowns.len())
+                ),
+                Some(date) == latest_displayed,
+            )?;
+            write_table_start(out, report)?;
+            table.write_header(out, false)?;
+            for (index, record) in reported_records(&records, min_followers)
+                .take(displayed_count)
+                .enumerate()
+            {
+                table.write_row(out, record, index + 1, None)?;
+            }
+            write_table_end(out, report)?;
+            write_hidden_rows_note(out, reported_count - displayed_count, report)?;
+
+            if show_unknown && !unknowns.is_empty() {
+                write_unknown_suspensions_table(out, unknowns, table)?;
+            }
+
+            report.write_details_end(out)
+        })?;
+    }
+
+    write_omitted_days_note(out, omitted, report)?;
+
+    Ok(())
+}
+
+/// A column of the suspension tables.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum SuspensionColumn {
+    Image,
+    Id,
+    ScreenName,
+    Created,
+    Age,
+    Reversed,
+    SuspendedFor,
+    Status,
+    Followers,
+    Archive,
+}
+
+impl SuspensionColumn {
+    /// The columns to show, given the `--columns` option and the flags that add optional columns
+    /// to the default layout.
+    fn resolve(columns: Option<Vec<Self>>, report: &ReportOpts) -> Vec<Self> {
+        let mut columns = columns.unwrap_or_else(|| {
+            let mut columns = vec![
+                Self::Image,
+                Self::Id,
+                Self::ScreenName,
+                Self::Created,
+                Self::Age,
+                Self::Reversed,
+                Self::SuspendedFor,
+                Self::Status,
+                Self::Followers,
+            ];
+            if report.archive_links {
+                columns.push(Self::Archive);
+            }
+            columns
+        });
+
+        // Redacted reports don't link to the accounts.
+        if report.redact {
+            columns.retain(|column| *column != Self::Archive);
+        }
+
+        columns
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            Self::Image => "",
+            Self::Id => "Twitter ID",
+            Self::ScreenName => "Screen name",
+            Self::Created => "Created",
+            Self::Age => "Age at suspension",
+            Self::Reversed => "Reversed",
+            Self::SuspendedFor => "Suspended for",
+            Self::Status => "Status",
+            Self::Followers => "Follower count",
+            Self::Archive => "Archive",
+        }
+    }
+
+    /// The alignment of the column's cells, when they aren't left-aligned.
+    fn align(self) -> Option<&'static str> {
+        match self {
+            Self::Status => Some("center"),
+            _ => None,
+        }
+    }
+}
+
+/// The options for rendering suspension tables.
+struct SuspensionsTable<'a> {
+    columns: &'a [SuspensionColumn],
+    thumbnails: &'a ThumbnailDir,
+    report: &'a ReportOpts,
+    spikes: Option<&'a HashSet<NaiveDate>>,
+    /// The text for the reversal of accounts that are still suspended.
+    still_suspended_label: &'a str,
+}
+
+impl SuspensionsTable<'_> {
+    fn is_spike(&self, date: NaiveDate) -> bool {
+        self.spikes.is_some_and(|spikes| spikes.contains(&date))
+    }
+
+    /// The marker for days with a spike in suspensions, to follow their contents entries.
+    ///
+    /// Headers don't include it, since it would change their anchors, so their summaries start
+    /// with it instead.
+    fn spike_marker(&self, date: NaiveDate) -> &'static str {
+        if self.is_spike(date) {
+            " 📈"
+        } else {
+            ""
+        }
+    }
+
+    /// The reversal date, or the still suspended label if there hasn't been one.
+    fn reversal_cell(&self, reversal: Option<DateTime<Utc>>) -> String {
+        match reversal {
+            Some(reversal) => reversal.format("%Y-%m-%d").to_string(),
+            None if self.report.pipe_tables() => escape_markdown(self.still_suspended_label),
+            None => escape_markup(self.still_suspended_label),
+        }
+    }
+
+    fn write_header(&self, out: &mut dyn Write, detected: bool) -> Result<(), Error> {
+        let mut headings = vec![];
+        if self.report.show_rank {
+            headings.push(("#", Some("right")));
+        }
+        if detected {
+            headings.push(("Detected", None));
+        }
+        headings.extend(
+            self.columns
+                .iter()
+                .map(|column| (column.header(), column.align())),
+        );
+
+        write_table_header(out, &headings, self.report)
+    }
+
+    /// Write a table row, starting with its (one-based) rank in the table if requested and the
+    /// date the suspension was reported under if given.
+    fn write_row(
+        &self,
+        out: &mut dyn Write,
+        record: &SuspensionRecord,
+        rank: usize,
+        detected: Option<NaiveDate>,
+    ) -> Result<(), Error> {
+        let report = self.report;
+
+        let mut cells = vec![];
+        if report.show_rank {
+            cells.push((rank.to_string(), Some("right")));
+        }
+        if let Some(date) = detected {
+            cells.push((date.format("%Y-%m-%d").to_string(), None));
+        }
+        for column in self.columns {
+            let cell = match column {
+                SuspensionColumn::Image => {
+                    profile_image(&record.profile_image_url, self.thumbnails, report)
+                }
+                SuspensionColumn::Id => user_id_link(record.user_id, report),
+                SuspensionColumn::ScreenName => screen_name_link(&record.screen_name, report),
+                SuspensionColumn::Created => record.created_at.format("%Y-%m-%d").to_string(),
+                SuspensionColumn::Age => format_account_age(record.created_at, record.timestamp),
+                SuspensionColumn::Reversed => self.reversal_cell(record.reversal),
+                SuspensionColumn::SuspendedFor => record
+                    .reversal
+                    .map(|value| format_duration(value - record.timestamp))
+                    .unwrap_or_default(),
+                SuspensionColumn::Status => {
+                    let mut status = String::new();
+                    if record.protected {
+                        status.push('🔒');
+                    }
+                    if record.verified {
+                        status.push_str("✔️");
+                    }
+                    status
+                }
+                SuspensionColumn::Followers => report.format_count(record.followers_count),
+                SuspensionColumn::Archive => archive_link(&record.screen_name, report),
+            };
+            cells.push((cell, column.align()));
+        }
+
+        write_table_row(out, &cells, report)
+    }
+}
+
+/// Format the age of an account at the given time, such as "3y 2mo" or "14d".
+///
+/// A dash is used if the account appears to have been created after the given time.
+fn format_account_age(created_at: DateTime<Utc>, at: DateTime<Utc>) -> String {
+    let days = (at - created_at).num_days();
+
+    if days < 0 {
+        return "-".to_string();
+    }
+
+    // Only whole calendar months are counted, so an account is a year old on its anniversary.
+    let (created_on, on) = (created_at.date_naive(), at.date_naive());
+    let total_months = (on.year() - created_on.year()) * 12 + on.month() as i32
+        - created_on.month() as i32
+        - i32::from(on.day() < created_on.day());
+    let years = total_months / 12;
+    let months = total_months % 12;
+
+    if years > 0 && months > 0 {
+        format!("{}y {}mo", years, months)
+    } else if years > 0 {
+        format!("{}y", years)
+    } else if months > 0 {
+        format!("{}mo", months)
+    } else {
+        format!("{}d", days)
+    }
+}
+
+/// The current UTC date, fixed for the whole run so that a report generated around midnight doesn't
+/// mix up relative dates.
+fn run_date() -> NaiveDate {
+    static RUN_DATE: OnceLock<NaiveDate> = OnceLock::new();
+
+    *RUN_DATE.get_or_init(|| Utc::now().date_naive())
+}
+
+/// Format a date relative to today, such as "Yesterday" or "3 days ago" (or nothing for future
+/// dates).
+fn format_relative_date(date: NaiveDate, today: NaiveDate) -> Option<String> {
+    match (today - date).num_days() {
+        0 => Some("Today".to_string()),
+        1 => Some("Yesterday".to_string()),
+        days if days > 1 => Some(format!("{} days ago", days)),
+        _ => None,
+    }
+}
+
+/// Format the time between a suspension and its reversal, such as "6d" or "3h".
+fn format_duration(duration: Duration) -> String {
+    if duration.num_days() > 0 {
+        format!("{}d", duration.num_days())
+    } else if duration.num_hours() > 0 {
+        format!("{}h", duration.num_hours())
+    } else {
+        format!("{}m", duration.num_minutes().max(0))
+    }
+}
+
+/// Write a table of the suspensions without account details for a single day.
+///
+/// These rows are likely to be deactivations or deletions rather than suspensions.
+fn write_unknown_suspensions_table(
+    out: &mut dyn Write,
+    mut unknowns: Vec<UnknownSuspension>,
+    table: &SuspensionsTable,
+) -> Result<(), Error> {
+    let report = table.report;
+    unknowns.sort_by_key(|unknown| unknown.timestamp);
+
+    writeln!(
+        out,
+        "\nFound {} accounts without details, which may have been deactivated or deleted rather than suspended.",
+        unknowns.len()
+    )?;
+    write_table_start(out, report)?;
+    write_table_header(
+        out,
+        &[("Detected", None), ("Twitter ID", None), ("Reversed", None)],
+        report,
+    )?;
+    for unknown in unknowns {
+        let id_link = unknown
+            .user_id
+            .map(|user_id| user_id_link(user_id, report))
+            .unwrap_or_default();
+        let reversal = table.reversal_cell(unknown.reversal);
+
+        write_table_row(
+            out,
+            &[
+                (
+                    unknown.timestamp.format(EVENT_TIMESTAMP_FORMAT).to_string(),
+                    None,
+                ),
+                (id_link, None),
+                (reversal, None),
+            ],
+            report,
+        )?;
+    }
+    write_table_end(out, report)?;
+
+    Ok(())
+}
+
+fn write_screen_names_csv(
+    out: &mut dyn Write,
+    date_records: &[(NaiveDate, Vec<ScreenNameRecord>)],
+    min_followers: usize,
+    days: usize,
+) -> Result<(), Error> {
+    write_csv(
+        out,
+        date_records
+            .iter()
+            .take(days)
+            .flat_map(|(_, records)| reported_records(records, min_followers)),
+    )
+}
+
+/// Note the number of rows left out of a table by `--max-rows-per-day`, if any.
+fn write_hidden_rows_note(
+    out: &mut dyn Write,
+    hidden_count: usize,
+    report: &ReportOpts,
+) -> Result<(), Error> {
+    if hidden_count > 0 {
+        writeln!(
+            out,
+            "\n… and {} more below the display cap",
+            report.format_count(hidden_count)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Note the days (and their reported row counts) left out because of the total row limit.
+fn write_omitted_days_note(
+    out: &mut dyn Write,
+    (omitted_days, omitted_rows): (usize, usize),
+    report: &ReportOpts,
+) -> Result<(), Error> {
+    if omitted_days > 0 {
+        writeln!(
+            out,
+            "\n… and {} more rows from {} earlier days omitted by the total row limit",
+            report.format_count(omitted_rows),
+            report.format_count(omitted_days)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Start a table in a Markdown report.
+///
+/// Pipe tables need a blank line before them, and end at the next blank line.
+fn write_table_start(out: &mut dyn Write, report: &ReportOpts) -> Result<(), Error> {
+    if report.pipe_tables() {
+        writeln!(out)?;
+    } else {
+        writeln!(out, "<table>")?;
+    }
+
+    Ok(())
+}
+
+fn write_table_end(out: &mut dyn Write, report: &ReportOpts) -> Result<(), Error> {
+    if !report.pipe_tables() {
+        writeln!(out, "</table>")?;
+    }
+
+    Ok(())
+}
+
+/// Write a table's header row, given the headings and the alignment of their columns' cells.
+///
+/// HTML headings are always left-aligned.
+fn write_table_header(
+    out: &mut dyn Write,
+    headings: &[(&str, Option<&str>)],
+    report: &ReportOpts,
+) -> Result<(), Error> {
+    if report.pipe_tables() {
+        write!(out, "|")?;
+        for (heading, _) in headings {
+            write!(out, " {} |", heading)?;
+        }
+        writeln!(out)?;
+
+        write!(out, "|")?;
+        for (_, align) in headings {
+            let delimiter = match align {
+                Some("center") => ":---:",
+                Some("right") => "---:",
+                _ => "---",
+            };
+            write!(out, " {} |", delimiter)?;
+        }
+        writeln!(out)?;
+    } else {
+        write!(out, "<tr>")?;
+        for (heading, _) in headings {
+            if heading.is_empty() {
+                write!(out, "<th></th>")?;
+            } else {
+                write!(out, "<th align=\"left\">{}</th>", heading)?;
+            }
+        }
+        writeln!(out, "</tr>")?;
+    }
+
+    Ok(())
+}
+
+/// Write a table row, given the cells' contents (already escaped) and alignments.
+fn write_table_row(
+    out: &mut dyn Write,
+    cells: &[(String, Option<&str>)],
+    report: &ReportOpts,
+) -> Result<(), Error> {
+    if report.pipe_tables() {
+        write!(out, "|")?;
+        for (cell, _) in cells {
+            write!(out, " {} |", cell)?;
+        }
+        writeln!(out)?;
+    } else {
+        write!(out, "<tr>")?;
+        for (cell, align) in cells {
+            match align {
+                Some(align) => write!(out, "<td align=\"{}\">{}</td>", align, cell)?,
+                None => write!(out, "<td>{}</td>", cell)?,
+            }
+        }
+        writeln!(out, "</tr>")?;
+    }
+
+    Ok(())
+}
+
+/// A linked profile image, using the local thumbnail if there is one.
+fn profile_image(
+    profile_image_url: &str,
+    thumbnails: &ThumbnailDir,
+    report: &ReportOpts,
+) -> String {
+    if report.redact {
+        return String::new();
+    }
+
+    let profile_image_url = https_profile_image_url(profile_image_url);
+    let profile_image_url = match &report.image_host {
+        Some(host) => Cow::Owned(rewrite_profile_image_host(&profile_image_url, host).into_owned()),
+        None => profile_image_url,
+    };
+    let image_url = make_profile_image_thumbnail_url(&profile_image_url, thumbnails);
+    if report.pipe_tables() {
+        return format!(
+            "[![]({})]({})",
+            escape_markdown_url(&image_url),
+            escape_markdown_url(&profile_image_url)
+        );
+    }
+
+    // Lazy images are only fetched when they're scrolled into view.
+    let loading = if report.lazy_images {
+        " loading=\"lazy\" decoding=\"async\""
+    } else {
+        ""
+    };
+
+    format!(
+        "<a href=\"{}\"><img src=\"{}\" width=\"40px\" height=\"40px\" align=\"center\"{}/></a>",
+        escape_markup(&profile_image_url),
+        escape_markup(&image_url),
+        loading
+    )
+}
+
+/// A user ID linked to the account's profile, which remains valid after screen name changes.
+fn user_id_link(user_id: u64, report: &ReportOpts) -> String {
+    if report.redact {
+        redact(&user_id.to_string())
+    } else if report.pipe_tables() {
+        format!(
+            "[{}](https://twitter.com/intent/user?user_id={})",
+            user_id, user_id
+        )
+    } else {
+        format!(
+            "<a href=\"https://twitter.com/intent/user?user_id={}\">{}</a>",
+            user_id, user_id
+        )
+    }
+}
+
+/// A screen name linked to the account's profile.
+fn screen_name_link(screen_name: &str, report: &ReportOpts) -> String {
+    if report.redact {
+        redact(screen_name)
+    } else if report.pipe_tables() {
+        format!(
+            "[{}](https://twitter.com/{})",
+            escape_markdown(&truncate_graphemes(screen_name, report.max_name_len)),
+            escape_markdown(screen_name)
+        )
+    } else {
+        format!(
+            "<a href=\"https://twitter.com/{}\">{}</a>",
+            escape_markup(screen_name),
+            escape_markup(&truncate_graphemes(screen_name, report.max_name_len))
+        )
+    }
+}
+
+/// A screen name without a link.
+fn screen_name_text(screen_name: &str, report: &ReportOpts) -> String {
+    if report.redact {
+        redact(screen_name)
+    } else if report.pipe_tables() {
+        escape_markdown(&truncate_graphemes(screen_name, report.max_name_len))
+    } else {
+        escape_markup(&truncate_graphemes(screen_name, report.max_name_len))
+    }
+}
+
+/// A stable pseudonym for an identifying value (the first eight hex digits of its SHA-256 hash).
+fn redact(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .take(4)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// A link to the Wayback Machine's most recent capture of the account's profile page.
+fn archive_link(screen_name: &str, report: &ReportOpts) -> String {
+    if report.pipe_tables() {
+        return format!(
+            "[archive](https://web.archive.org/web/2/https://twitter.com/{})",
+            escape_markdown(screen_name)
+        );
+    }
+
+    format!(
+        "<a href=\"https://web.archive.org/web/2/https://twitter.com/{}\">archive</a>",
+        escape_markup(screen_name)
+    )
+}
+
+#[derive(serde::Serialize)]
+struct SuspensionsDayJson<'a> {
+    date: NaiveDate,
+    suspensions: Vec<&'a SuspensionRecord>,
+    unknown_count: usize,
+}
+
+fn write_suspensions_json(
+    out: &mut dyn Write,
+    date_records: &[SuspensionsDay],
+    min_followers: usize,
+    days: usize,
+) -> Result<(), Error> {
+    let days = date_records
+        .iter()
+        .take(days)
+        .map(|(date, records, unknowns)| SuspensionsDayJson {
+            date: *date,
+            suspensions: reported_records(records, min_followers).collect(),
+            unknown_count: unknowns.len(),
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_writer(&mut *out, &days)?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn write_suspensions_csv(
+    out: &mut dyn Write,
+    date_records: &[SuspensionsDay],
+    min_followers: usize,
+    days: usize,
+) -> Result<(), Error> {
+    write_csv(
+        out,
+        date_records
+            .iter()
+            .take(days)
+            .flat_map(|(_, records, _)| reported_records(records, min_followers)),
+    )
+}
+
+/// Write records as CSV with a header row, in the same column order as the data file.
+fn write_csv<'a, R: CsvRecord + 'a, I: IntoIterator<Item = &'a R>>(
+    out: &mut dyn Write,
+    records: I,
+) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(R::HEADER)?;
+
+    for record in records {
+        writer.write_record(record.csv_fields())?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// The ANSI escape codes used to highlight verified accounts in the plain text format.
+const TEXT_HIGHLIGHT: &str = "\x1b[1m";
+const TEXT_RESET: &str = "\x1b[0m";
+
+/// The width of the plain text tables if neither `--width` nor `$COLUMNS` is set.
+const DEFAULT_TEXT_WIDTH: usize = 80;
+
+/// The space between plain text table columns.
+const TEXT_COLUMN_SEPARATOR: &str = "  ";
+
+/// Write the reported records for each day as a plain text table.
+///
+/// The columns are aligned across all of the days, and screen names are truncated so that the
+/// rows fit in the report's text width where possible. Verified accounts are shown in bold if
+/// colors are enabled.
+fn write_text<'a, A: TextRecord + 'a>(
+    out: &mut dyn Write,
+    days: impl Iterator<Item = (NaiveDate, &'a [A])>,
+    min_followers: usize,
+    report: &ReportOpts,
+) -> Result<(), Error> {
+    let days = days
+        .map(|(date, records)| {
+            let rows = reported_records(records, min_followers)
+                .map(|record| {
+                    let cells = [
+                        record.user_id().to_string(),
+                        record.text_names().join(" → "),
+                        report.format_count(record.followers_count()),
+                        text_status(record),
+                    ];
+                    (record, cells)
+                })
+                .collect::<Vec<_>>();
+            (date, rows)
+        })
+        .collect::<Vec<_>>();
+
+    let headings = ["Twitter ID", A::TEXT_NAMES_HEADING, "Followers", "Status"];
+    let mut widths = headings.map(grapheme_len);
+    for (_, cells) in days.iter().flat_map(|(_, rows)| rows) {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(grapheme_len(cell));
+        }
+    }
+
+    // The names get whatever is left of the width after the other columns.
+    let fixed_width = widths[0] + widths[2] + widths[3] + 3 * TEXT_COLUMN_SEPARATOR.len();
+    let names_width = report
+        .text_width()
+        .saturating_sub(fixed_width)
+        .max(grapheme_len(A::TEXT_NAMES_HEADING));
+    widths[1] = widths[1].min(names_width);
+
+    let color = report.use_color();
+    let write_line = |out: &mut dyn Write, cells: &[String], highlight: bool| {
+        let line = cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(index, (cell, width))| {
+                let padding = " ".repeat(width.saturating_sub(grapheme_len(cell)));
+                // Follower counts are right-aligned.
+                if index == 2 {
+                    format!("{}{}", padding, cell)
+                } else {
+                    format!("{}{}", cell, padding)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(TEXT_COLUMN_SEPARATOR);
+        let line = line.trim_end();
+
+        if highlight {
+            writeln!(out, "{}{}{}", TEXT_HIGHLIGHT, line, TEXT_RESET)
+        } else {
+            writeln!(out, "{}", line)
+        }
+    };
+
+    for (index, (date, rows)) in days.iter().enumerate() {
+        if index > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "{}", report.format_header(*date))?;
+        write_line(out, &headings.map(str::to_string), false)?;
+
+        for (record, cells) in rows {
+            let names = record.text_names();
+            let mut cells = cells.clone();
+
+            if grapheme_len(&cells[1]) > widths[1] {
+                // Each name gets an equal share of the column, apart from the arrows.
+                let name_width = widths[1].saturating_sub(3 * (names.len() - 1)) / names.len();
+                cells[1] = names
+                    .iter()
+                    .map(|name| fit_graphemes(name, name_width))
+                    .collect::<Vec<_>>()
+                    .join(" → ");
+            }
+
+            write_line(out, &cells, color && record.verified())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the number of reported records per day in each follower count range as CSV.
+///
+/// The bounds are the lower ends of the ranges after the first, which starts at zero. Every record
+/// is counted, without the tables' follower count threshold, so that the first range really does
+/// start at zero.
+fn write_buckets_csv<'a, A: Account + 'a>(
+    out: &mut dyn Write,
+    days: impl Iterator<Item = (NaiveDate, &'a [A])>,
+    bounds: &[usize],
+) -> Result<(), Error> {
+    let mut bounds = bounds.to_vec();
+    bounds.sort_unstable();
+    bounds.dedup();
+    bounds.retain(|bound| *bound > 0);
+
+    let mut header = vec!["date".to_string()];
+    let mut lower = 0;
+    for bound in &bounds {
+        header.push(format!("{}-{}", lower, bound - 1));
+        lower = *bound;
+    }
+    header.push(format!("{}+", lower));
+
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(&header)?;
+
+    for (date, records) in days {
+        let mut counts = vec![0; bounds.len() + 1];
+        for record in records {
+            let followers_count = record.followers_count();
+            counts[bounds.partition_point(|bound| *bound <= followers_count)] += 1;
+        }
+
+        writer.write_record(
+            std::iter::once(date.to_string()).chain(counts.iter().map(|count| count.to_string())),
+        )?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Render a Markdown report as a standalone HTML document.
+///
+/// Headings are given the same anchors GitHub would generate for the contents links, and the
+/// follower count column (given by its one-based index) is right-aligned.
+fn write_html<F: FnOnce(&mut dyn Write) -> Result<(), Error>>(
+    out: &mut dyn Write,
+    title: &str,
+    followers_column: Option<usize>,
+    write_markdown: F,
+) -> Result<(), Error> {
+    let mut markdown = vec![];
+    write_markdown(&mut markdown)?;
+    let markdown = String::from_utf8_lossy(&markdown);
+
+    let mut events = vec![];
+    let mut heading_start = None;
+    let mut heading_text = String::new();
+
+    for event in pulldown_cmark::Parser::new(&markdown) {
+        match &event {
+            Event::Start(Tag::Heading { .. }) => {
+                heading_start = Some(events.len());
+                heading_text.clear();
+            }
+            Event::Text(text) if heading_start.is_some() => heading_text.push_str(text),
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(Event::Start(Tag::Heading { id, .. })) =
+                    heading_start.take().map(|index| &mut events[index])
+                {
+                    *id = Some(heading_anchor(&heading_text).into());
+                }
+            }
+            _ => {}
+        }
+
+        events.push(event);
+    }
+
+    let mut body = String::new();
+    pulldown_cmark::html::push_html(&mut body, events.into_iter());
+
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>{}</title>", escape_markup(title))?;
+    writeln!(out, "<style>")?;
+    writeln!(
+        out,
+        "body {{ font-family: sans-serif; margin: 2em auto; max-width: 60em; }}"
+    )?;
+    writeln!(out, "table {{ border-collapse: collapse; }}")?;
+    writeln!(out, "th, td {{ padding: 0.25em 0.5em; }}")?;
+    writeln!(out, "tr:nth-child(even) {{ background-color: #f2f2f2; }}")?;
+    if let Some(followers_column) = followers_column {
+        writeln!(
+            out,
+            "td:nth-child({}) {{ text-align: right; }}",
+            followers_column
+        )?;
+    }
+    writeln!(out, "</style>")?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    write!(out, "{}", body)?;
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+
+    Ok(())
+}
+
+/// Write a report to the given file or to stdout.
+///
+/// Files are written to a temporary path and renamed when complete, so a failed run never leaves
+/// a partially written report behind.
+fn write_output<F: FnOnce(&mut dyn Write) -> Result<(), Error>>(
+    output: Option<&str>,
+    write: F,
+) -> Result<(), Error> {
+    match output {
+        Some(output) => {
+            let path = Path::new(output);
+            let mut temp_file_name = OsString::from(".");
+            temp_file_name.push(path.file_name().unwrap_or_default());
+            temp_file_name.push(".tmp");
+            let temp_path = path.with_file_name(temp_file_name);
+
+            let result = File::create(&temp_path)
+                .map_err(Error::from)
+                .and_then(|file| {
+                    let mut writer = BufWriter::new(file);
+                    write(&mut writer)?;
+                    writer
+                        .into_inner()
+                        .map_err(|error| error.into_error())?
+                        .sync_all()?;
+                    Ok(())
+                });
+
+            match result {
+                Ok(()) => Ok(std::fs::rename(&temp_path, path)?),
+                Err(error) => {
+                    let _ = std::fs::remove_file(&temp_path);
+                    Err(error)
+                }
+            }
+        }
+        None => {
+            let stdout = std::io::stdout();
+            let mut writer = BufWriter::new(stdout.lock());
+            write(&mut writer)?;
+            writer.flush()?;
+            Ok(())
+        }
+    }
+}
+
+/// Either fail on a malformed row or log it and count it as skipped.
+fn skip_or_fail(
+    skip_bad_rows: bool,
+    skipped: &mut usize,
+) -> impl FnMut(Error) -> Result<(), Error> + '_ {
+    move |error| {
+        if skip_bad_rows {
+            log::warn!("{}", error);
+            *skipped += 1;
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+}
+
+/// Wall-clock durations of the phases of a report, printed to stderr if enabled.
+struct Timing {
+    enabled: bool,
+    phase_started: Instant,
+    phases: Vec<(&'static str, std::time::Duration)>,
+}
+
+impl Timing {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            phase_started: Instant::now(),
+            phases: vec![],
+        }
+    }
+
+    /// Record the end of a phase, which started at the end of the previous one.
+    fn phase(&mut self, name: &'static str) {
+        let now = Instant::now();
+        self.phases.push((name, now - self.phase_started));
+        self.phase_started = now;
+    }
+
+    fn report(&self) {
+        if self.enabled {
+            for (name, duration) in &self.phases {
+                log::info!("{:<16}{:>10.2?}", name, duration);
+            }
+            log::info!(
+                "{:<16}{:>10.2?}",
+                "total",
+                self.phases
+                    .iter()
+                    .map(|(_, duration)| *duration)
+                    .sum::<std::time::Duration>()
+            );
+        }
+    }
+}
+
+fn report_skipped(skipped: usize) {
+    if skipped > 0 {
+        log::warn!("Skipped {} malformed rows", skipped);
+    }
+}
+
+/// Backslash-escape the ASCII punctuation in text for Markdown (including pipes, for tables).
+fn escape_markdown(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if c.is_ascii_punctuation() {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+/// Percent-encode the characters that would end a Markdown link destination or a table cell.
+fn escape_markdown_url(url: &str) -> String {
+    let mut result = String::with_capacity(url.len());
+
+    for c in url.chars() {
+        if c.is_whitespace() || matches!(c, '|' | '(' | ')' | '<' | '>' | '\\') {
+            for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                result.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// Escape a string for inclusion in HTML or XML text or attribute values.
+fn escape_markup(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Account details shared by the record types.
+trait Account {
+    fn timestamp(&self) -> DateTime<Utc>;
+    fn user_id(&self) -> u64;
+    fn verified(&self) -> bool;
+    fn protected(&self) -> bool;
+    fn followers_count(&self) -> usize;
+    fn profile_image_url(&self) -> &str;
+    /// Whether any of the record's screen names match the pattern.
+    fn matches_screen_name(&self, pattern: &Regex) -> bool;
+}
+
+/// A record that can be shown in the plain text format.
+trait TextRecord: Account {
+    /// The heading of the screen names column.
+    const TEXT_NAMES_HEADING: &'static str;
+
+    /// The screen names to show, which may be truncated to fit.
+    fn text_names(&self) -> Vec<&str>;
+}
+
+/// The status of an account in words, for the plain text format.
+fn text_status<A: Account>(account: &A) -> String {
+    let mut status = vec![];
+    if account.verified() {
+        status.push("verified");
+    }
+    if account.protected() {
+        status.push("locked");
+    }
+
+    status.join(", ")
+}
+
+/// A record that can be written back out in the data file format.
+trait CsvRecord {
+    const HEADER: &'static [&'static str];
+
+    fn csv_fields(&self) -> Vec<String>;
+}
+
+impl Account for ScreenNameRecord {
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn user_id(&self) -> u64 {
+        self.user_id
+    }
+
+    fn verified(&self) -> bool {
+        self.verified
+    }
+
+    fn protected(&self) -> bool {
+        self.protected
+    }
+
+    fn followers_count(&self) -> usize {
+        self.followers_count
+    }
+
+    fn profile_image_url(&self) -> &str {
+        &self.profile_image_url
+    }
+
+    fn matches_screen_name(&self, pattern: &Regex) -> bool {
+        pattern.is_match(&self.previous_screen_name) || pattern.is_match(&self.new_screen_name)
+    }
+}
+
+impl TextRecord for ScreenNameRecord {
+    const TEXT_NAMES_HEADING: &'static str = "Screen name change";
+
+    fn text_names(&self) -> Vec<&str> {
+        vec![&self.previous_screen_name, &self.new_screen_name]
+    }
+}
+
+impl CsvRecord for ScreenNameRecord {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp",
+        "user_id",
+        "verified",
+        "protected",
+        "followers_count",
+        "previous_screen_name",
+        "new_screen_name",
+        "profile_image_url",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.timestamp.timestamp().to_string(),
+            self.user_id.to_string(),
+            self.verified.to_string(),
+            self.protected.to_string(),
+            self.followers_count.to_string(),
+            self.previous_screen_name.clone(),
+            self.new_screen_name.clone(),
+            self.profile_image_url.clone(),
+        ]
+    }
+}
+
+impl Account for SuspensionRecord {
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn user_id(&self) -> u64 {
+        self.user_id
+    }
+
+    fn verified(&self) -> bool {
+        self.verified
+    }
+
+    fn protected(&self) -> bool {
+        self.protected
+    }
+
+    fn followers_count(&self) -> usize {
+        self.followers_count
+    }
+
+    fn profile_image_url(&self) -> &str {
+        &self.profile_image_url
+    }
+
+    fn matches_screen_name(&self, pattern: &Regex) -> bool {
+        pattern.is_match(&self.screen_name)
+    }
+}
+
+impl TextRecord for SuspensionRecord {
+    const TEXT_NAMES_HEADING: &'static str = "Screen name";
+
+    fn text_names(&self) -> Vec<&str> {
+        vec![&self.screen_name]
+    }
+}
+
+impl CsvRecord for SuspensionRecord {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp",
+        "reversal",
+        "user_id",
+        "created_at",
+        "screen_name",
+        "verified",
+        "protected",
+        "followers_count",
+        "profile_image_url",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.timestamp.timestamp().to_string(),
+            self.reversal
+                .map(|reversal| reversal.timestamp().to_string())
+                .unwrap_or_default(),
+            self.user_id.to_string(),
+            self.created_at.timestamp().to_string(),
+            self.screen_name.clone(),
+            self.verified.to_string(),
+            self.protected.to_string(),
+            self.followers_count.to_string(),
+            self.profile_image_url.clone(),
+        ]
+    }
+}
+
+/// A suspensions row, which may be missing the account details.
+enum SuspensionRow {
+    Suspension(SuspensionRecord),
+    Unknown(UnknownSuspension),
+}
+
+/// A suspensions row without account details, which may be a deactivation or deletion.
+struct UnknownSuspension {
+    timestamp: DateTime<Utc>,
+    reversal: Option<DateTime<Utc>>,
+    user_id: Option<u64>,
+}
+
+/// The suspensions detected on a single day, with the rows missing account details kept separate.
+type SuspensionsDay = (NaiveDate, Vec<SuspensionRecord>, Vec<UnknownSuspension>);
+
+impl SuspensionRow {
+    fn into_record(self) -> Option<SuspensionRecord> {
+        match self {
+            Self::Suspension(record) => Some(record),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl TryFrom<csv::StringRecord> for SuspensionRow {
+    type Error = Error;
+
+    fn try_from(value: csv::StringRecord) -> Result<Self, Self::Error> {
+        if value.get(3) == Some("") {
+            let timestamp =
+                parse_timestamp(&value[0]).ok_or_else(|| Error::InvalidSuspensionsRecord {
+                    line: record_line(&value),
+                    record: value.clone(),
+                })?;
+
+            Ok(Self::Unknown(UnknownSuspension {
+                timestamp,
+                reversal: value.get(1).and_then(parse_timestamp),
+                user_id: value.get(2).and_then(|user_id| user_id.parse().ok()),
+            }))
+        } else {
+            SuspensionRecord::try_from(value).map(Self::Suspension)
+        }
+    }
+}
+
+impl Record for SuspensionRow {
+    type SortKey = Option<<SuspensionRecord as Record>::SortKey>;
+    const SCHEMA: data::Schema = data::Schema::Suspensions;
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            Self::Suspension(record) => record.timestamp,
+            Self::Unknown(unknown) => unknown.timestamp,
+        }
+    }
+
+    fn sort_key(&self) -> Self::SortKey {
+        match self {
+            Self::Suspension(record) => Some(record.sort_key()),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    fn problems(&self, now: DateTime<Utc>) -> Vec<String> {
+        match self {
+            Self::Suspension(record) => record.problems(now),
+            Self::Unknown(unknown) => {
+                let mut problems = vec![];
+                problems.extend(timestamp_problem("timestamp", unknown.timestamp, now));
+                if let Some(reversal) = unknown.reversal {
+                    problems.extend(timestamp_problem("reversal", reversal, now));
+                }
+                if unknown.user_id == Some(0) {
+                    problems.push("user_id is zero".to_string());
+                }
+                problems
+            }
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+#[clap(name = "report", version, author)]
+struct Opts {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Parser)]
+enum Command {
+    ScreenNames(ScreenNamesOpts),
+    Suspensions(SuspensionsOpts),
+    /// Print the full history for a single account
+    Search {
+        /// Data directory (defaults to the directory for the selected kind)
+        #[clap(long)]
+        base: Option<String>,
+        /// Kind of data to search
+        #[clap(long, arg_enum, default_value = "screen-names")]
+        kind: Kind,
+        /// Twitter user ID
+        user_id: u64,
+        #[clap(flatten)]
+        read: ReadOpts,
+    },
+    /// Print summary statistics for a dataset
+    Stats {
+        /// Data directory (defaults to the directory for the selected kind)
+        #[clap(long)]
+        base: Option<String>,
+        /// Kind of data to summarize
+        #[clap(long, arg_enum, default_value = "screen-names")]
+        kind: Kind,
+        /// Only include accounts with at least this many followers
+        #[clap(long)]
+        min_followers: Option<usize>,
+        #[clap(flatten)]
+        read: ReadOpts,
+    },
+    /// List the times accounts gained or lost verification, from the screen name data
+    VerificationChanges {
+        /// Screen name directory
+        #[clap(long, default_value = "screen-names/")]
+        base: String,
+        #[clap(flatten)]
+        read: ReadOpts,
+    },
+    /// Print a short summary combining the screen name and suspension data
+    Summary {
+        /// Screen name directory
+        #[clap(long, default_value = "screen-names/")]
+        screen_names_base: String,
+        /// Suspensions directory
+        #[clap(long, default_value = "suspensions/")]
+        suspensions_base: String,
+        #[clap(flatten)]
+        read: ReadOpts,
+    },
+    /// List the records in a new snapshot of a data file that aren't in an older one
+    Diff {
+        /// Older data file
+        old: String,
+        /// Newer data file
+        new: String,
+        /// Kind of data to compare
+        #[clap(long, arg_enum, default_value = "screen-names")]
+        kind: Kind,
+        #[clap(flatten)]
+        read: ReadOpts,
+    },
+    /// List the reported accounts whose profile images don't have local thumbnails, selected and
+    /// resolved with the same options as the report
+    CheckThumbnails {
+        /// Data directory (defaults to the directory for the selected kind)
+        #[clap(long)]
+        base: Option<String>,
+        /// Kind of data to check
+        #[clap(long, arg_enum, default_value = "screen-names")]
+        kind: Kind,
+        /// Minimum follower count for accounts to check (defaults to the report's)
+        #[clap(long)]
+        min_followers: Option<usize>,
+        #[clap(flatten)]
+        report: ReportOpts,
+    },
+    /// Write both reports and an index linking them to a directory (ignoring --output, and without
+    /// support for --split-by-day)
+    Dashboard {
+        /// Screen name directory (may be repeated to merge several directories)
+        #[clap(long, default_value = "screen-names/")]
+        screen_names_base: Vec<String>,
+        /// Suspensions directory (may be repeated to merge several directories)
+        #[clap(long, default_value = "suspensions/")]
+        suspensions_base: Vec<String>,
+        /// Minimum follower count for accounts included in the screen name tables
+        #[clap(long, default_value = "200")]
+        screen_names_min_followers: usize,
+        /// Minimum follower count for accounts included in the suspension tables
+        #[clap(long, default_value = "200")]
+        suspensions_min_followers: usize,
+        /// Directory to write the reports and index to
+        #[clap(long)]
+        out_dir: PathBuf,
+        #[clap(flatten)]
+        report: ReportOpts,
+    },
+}
+
+impl Command {
+    fn report_opts(&self) -> Option<&ReportOpts> {
+        match self {
+            Command::ScreenNames(opts) => Some(&opts.report),
+            Command::Suspensions(opts) => Some(&opts.report),
+            Command::Dashboard { report, .. } | Command::CheckThumbnails { report, .. } => {
+                Some(report)
+            }
+            _ => None,
+        }
+    }
+
+    fn read_opts(&self) -> &ReadOpts {
+        match self {
+            Command::ScreenNames(opts) => &opts.report.read,
+            Command::Suspensions(opts) => &opts.report.read,
+            Command::Dashboard { report, .. } | Command::CheckThumbnails { report, .. } => {
+                &report.read
+            }
+            Command::Search { read, .. }
+            | Command::Stats { read, .. }
+            | Command::VerificationChanges { read, .. }
+            | Command::Summary { read, .. }
+            | Command::Diff { read, .. } => read,
+        }
+    }
+}
+
+/// The options of a subcommand when none are given on the command line.
+fn default_opts<T: Args + FromArgMatches>() -> T {
+    let matches = T::augment_args(App::new("report")).get_matches_from(["report"]);
+
+    T::from_arg_matches(&matches).expect("subcommand options have defaults")
+}
+
+/// Options for the screen name change report.
+#[derive(Args, Debug)]
+struct ScreenNamesOpts {
+    /// Screen name directory (may be repeated to merge several directories), or - to read the data
+    /// from stdin (with thumbnails only from --thumbnails)
+    #[clap(long, default_value = "screen-names/")]
+    base: Vec<String>,
+    /// Minimum follower count for accounts included in the tables
+    #[clap(long, default_value = "200")]
+    min_followers: usize,
+    #[clap(flatten)]
+    report: ReportOpts,
+    /// Collapse multiple changes for an account on the same day into one row
+    #[clap(long)]
+    collapse: bool,
+    /// Show the change in follower count since the account's previous appearance
+    #[clap(long)]
+    show_deltas: bool,
+    /// Mark screen name changes involving a screen name that was also used by another account
+    #[clap(long)]
+    detect_reuse: bool,
+    /// Mark screen name changes back to a screen name the account used before
+    #[clap(long)]
+    detect_reverts: bool,
+    /// Only include accounts with at least this many screen name changes in the reported days
+    #[clap(long, default_value = "1")]
+    min_changes: usize,
+    /// Comma-separated table columns to show, in order (Markdown and HTML only)
+    #[clap(long, arg_enum, use_delimiter = true)]
+    columns: Option<Vec<ScreenNameColumn>>,
+    /// Only include changes where the account's follower count grew by more than this percentage
+    /// since its previous appearance (excluding accounts without one)
+    #[clap(long, value_name = "PERCENT")]
+    min_follower_growth: Option<f64>,
+}
+
+/// Options for the suspension report.
+#[derive(Args, Debug)]
+struct SuspensionsOpts {
+    /// Suspensions directory (may be repeated to merge several directories), or - to read the data
+    /// from stdin (with thumbnails only from --thumbnails)
+    #[clap(long, default_value = "suspensions/")]
+    base: Vec<String>,
+    /// Minimum follower count for accounts included in the tables
+    #[clap(long, alias = "min-followers", default_value = "200")]
+    table_min_followers: u64,
+    /// Minimum follower count for accounts counted as included in each day's summary, which can be
+    /// lower than the table's to show how many were left out of it (Markdown and HTML only)
+    #[clap(long, default_value = "200")]
+    count_min_followers: u64,
+    #[clap(flatten)]
+    report: ReportOpts,
+    /// Include a table of the suspensions without account details for each day
+    #[clap(long)]
+    show_unknown: bool,
+    /// Comma-separated table columns to show, in order (Markdown and HTML only)
+    #[clap(long, arg_enum, use_delimiter = true)]
+    columns: Option<Vec<SuspensionColumn>>,
+    /// Mark days with more than this many times the average number of suspensions of the previous
+    /// seven days with 📈 (Markdown and HTML only)
+    #[clap(long)]
+    spike_threshold: Option<f64>,
+    /// Only include the most recent suspension for each account in the reported days
+    #[clap(long)]
+    latest_only: bool,
+    /// Text to show in the reversal column for accounts that are still suspended
+    #[clap(long, default_value = "—")]
+    still_suspended_label: String,
+    /// Only include accounts that were suspended less than this many days after they were created
+    #[clap(long, value_name = "N")]
+    max_account_age_days: Option<u32>,
+}
+
+/// Options shared by the report subcommands.
+#[derive(Args, Clone, Debug)]
+struct ReportOpts {
+    /// Number of days (or weeks or months, when grouping by them) to report
+    #[clap(long, default_value = "7")]
+    days: usize,
+    /// Only report days on or after this date (YYYY-MM-DD)
+    #[clap(long)]
+    since: Option<NaiveDate>,
+    /// Only report days on or before this date (YYYY-MM-DD)
+    #[clap(long)]
+    until: Option<NaiveDate>,
+    /// Drop records from more than this many days ago while loading the data, for everything in
+    /// the report
+    #[clap(long, value_name = "N")]
+    max_age_days: Option<u32>,
+    /// Period to group records by
+    #[clap(long, arg_enum, default_value = "day")]
+    group_by: GroupBy,
+    /// Order of the reported days
+    #[clap(long, arg_enum, default_value = "desc")]
+    sort: SortOrder,
+    /// Order of the rows for each day
+    #[clap(long, arg_enum, default_value = "followers")]
+    sort_by: SortBy,
+    /// Factor that verified accounts' follower counts are multiplied by for --sort-by impact
+    #[clap(long, default_value = "10")]
+    verified_multiplier: f64,
+    /// Output format
+    #[clap(long, arg_enum, default_value = "markdown")]
+    format: Format,
+    /// Comma-separated follower counts where the ranges of the buckets format start
+    #[clap(long, use_delimiter = true, default_value = "1000,10000,100000")]
+    buckets: Vec<usize>,
+    /// Log and skip malformed rows instead of failing
+    #[clap(long)]
+    skip_bad_rows: bool,
+    /// Write the report to this file instead of stdout
+    #[clap(long)]
+    output: Option<String>,
+    /// When to highlight verified accounts with ANSI codes (text format only)
+    #[clap(long, arg_enum, default_value = "auto")]
+    color: ColorMode,
+    /// Width of the plain text tables in characters (defaults to $COLUMNS, or 80 if it isn't set)
+    #[clap(long)]
+    width: Option<usize>,
+    /// Write each day's section to its own file in this directory, with an index.md linking them
+    /// (instead of writing to --output)
+    #[clap(long)]
+    split_by_day: Option<PathBuf>,
+    /// Data file to read instead of the one in the base directory (may be gzipped)
+    #[clap(long)]
+    file: Option<String>,
+    /// Assume the rows of each data file are in timestamp order, keeping only the reported days in
+    /// memory while reading and stopping at the first row after --until (faster for large files,
+    /// but older data is discarded, so for example follower deltas only cover the reported days;
+    /// the report is still only written once loading has finished)
+    #[clap(long)]
+    assume_sorted: bool,
+    /// Read data.csv.gz even if data.csv is also present
+    #[clap(long)]
+    prefer_compressed: bool,
+    /// Thumbnail directory (defaults to the thumbnails directory in the base directory)
+    #[clap(long)]
+    thumbnails: Option<String>,
+    /// Local image to show for profile image URLs that can't have thumbnails (such as default
+    /// avatars)
+    #[clap(long)]
+    placeholder: Option<String>,
+    /// List the thumbnail directory once up front instead of checking for each thumbnail file
+    /// (faster on slow storage)
+    #[clap(long)]
+    index_thumbnails: bool,
+    /// Only include verified accounts
+    #[clap(long)]
+    verified_only: bool,
+    /// Only include protected accounts
+    #[clap(long)]
+    protected_only: bool,
+    /// Only include accounts with a screen name matching this regular expression
+    #[clap(long)]
+    screen_name_regex: Option<Regex>,
+    /// File listing user IDs (one per line) to leave out of the report
+    #[clap(long = "exclude-file", parse(try_from_str = parse_user_id_file))]
+    excluded_user_ids: Option<HashSet<u64>>,
+    /// File listing the only user IDs (one per line) to report, regardless of follower count
+    #[clap(
+        long = "watchlist-file",
+        parse(try_from_str = parse_user_id_file),
+        conflicts_with = "excluded-user-ids"
+    )]
+    watched_user_ids: Option<HashSet<u64>>,
+    /// Maximum number of rows to display in each table
+    #[clap(long)]
+    max_rows_per_day: Option<usize>,
+    /// Truncate displayed screen names longer than this many characters
+    #[clap(long, default_value = "30")]
+    max_name_len: usize,
+    /// Host to link profile images from instead of the one in the data (such as a mirror)
+    #[clap(long)]
+    image_host: Option<String>,
+    /// Size of the thumbnail variant to use, in pixels
+    #[clap(long, default_value = "400", parse(try_from_str = parse_profile_image_size))]
+    profile_image_size: u32,
+    /// Let browsers defer loading profile images until they're scrolled into view (HTML tables
+    /// only)
+    #[clap(long)]
+    lazy_images: bool,
+    /// Add a column linking to archived copies of each account's profile
+    #[clap(long)]
+    archive_links: bool,
+    /// Add a column with each row's rank in its table
+    #[clap(long)]
+    show_rank: bool,
+    /// Make each day's tables collapsible, with only the most recent day expanded (Markdown and
+    /// HTML only)
+    #[clap(long)]
+    collapsible: bool,
+    /// Replace screen names and user IDs with hashes and leave out links and profile images
+    /// (Markdown and HTML only)
+    #[clap(long)]
+    redact: bool,
+    /// Only write the day sections, leaving out the introduction and contents (Markdown and HTML
+    /// only)
+    #[clap(long)]
+    quiet: bool,
+    /// File whose contents are used in place of the built-in title and introduction (Markdown
+    /// and HTML only)
+    #[clap(long)]
+    header_file: Option<PathBuf>,
+    #[clap(flatten)]
+    read: ReadOpts,
+    /// Log how long reading, grouping, and writing the report took to stderr (rows are parsed
+    /// as they're read, so those are timed together)
+    #[clap(long)]
+    timing: bool,
+    /// Warn about records with implausible values (such as timestamps from before Twitter
+    /// launched), which usually indicate misordered columns
+    #[clap(long)]
+    validate: bool,
+    /// Maximum number of rows to display across all days, with the most recent days filled first
+    #[clap(long)]
+    limit_total: Option<usize>,
+    /// Show a single table of the N accounts with the most followers in the reported period
+    /// instead of a section for each day (Markdown and HTML only)
+    #[clap(long, parse(try_from_str = parse_top))]
+    top: Option<usize>,
+    /// Locale for header dates and count digit grouping (e.g. de_DE)
+    #[clap(long, parse(try_from_str = parse_locale))]
+    locale: Option<Locale>,
+    /// Show day headers relative to the current UTC date ("Today", "Yesterday", "3 days ago")
+    #[clap(long)]
+    relative_dates: bool,
+    /// Leave out the footer noting when the report was generated (Markdown and HTML only)
+    #[clap(long)]
+    no_footer: bool,
+    /// Only print the number of records that would be included for each day, without writing the
+    /// report
+    #[clap(long)]
+    dry_run: bool,
+    /// URL the report is published at, used for absolute links in HTML and RSS output
+    #[clap(long, value_name = "URL")]
+    base_url: Option<String>,
+}
+
+/// Options for reading the data files, shared by every subcommand that loads them.
+#[derive(Args, Clone, Debug)]
+struct ReadOpts {
+    /// Field delimiter used in the data files (a single character, or "tab")
+    #[clap(long, default_value = ",", parse(try_from_str = parse_delimiter))]
+    delimiter: u8,
+    /// Whether the first row of each data file is a header to skip (the published dumps don't
+    /// have one, so their first record is only read with `--has-header false`)
+    #[clap(long, default_value = "true", parse(try_from_str))]
+    has_header: bool,
+    /// Log the number of rows read to stderr while loading large data files
+    #[clap(long)]
+    progress: bool,
+}
+
+impl ReadOpts {
+    fn options(&self) -> ReadOptions {
+        ReadOptions {
+            delimiter: self.delimiter,
+            has_headers: self.has_header,
+            show_progress: self.progress,
+        }
+    }
+}
+
+/// Resolve a relative link (a fragment or a path) against a base URL, as a browser would.
+fn resolve_url(base_url: &str, link: &str) -> String {
+    let page = base_url.split('#').next().unwrap_or_default();
+
+    if let Some(fragment) = link.strip_prefix('#') {
+        return format!("{}#{}", page, fragment);
+    }
+
+    let page = page.split('?').next().unwrap_or_default();
+    let path_start = page.find("://").map_or(0, |index| index + 3);
+    let link = link.trim_start_matches("./");
+
+    match page[path_start..].rfind('/') {
+        Some(index) => format!("{}{}", &page[..path_start + index + 1], link),
+        None => format!("{}/{}", page, link),
+    }
+}
+
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value.as_bytes() {
+        b"tab" => Ok(b'\t'),
+        [delimiter] => Ok(*delimiter),
+        _ => Err(format!(
+            "expected a single ASCII character or \"tab\": {}",
+            value
+        )),
+    }
+}
+
+/// Read a file of user IDs, one per line (blank lines are ignored).
+fn parse_user_id_file(value: &str) -> Result<HashSet<u64>, String> {
+    let contents =
+        std::fs::read_to_string(value).map_err(|error| format!("{}: {}", value, error))?;
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            line.trim().parse::<u64>().map_err(|_| {
+                format!(
+                    "{}: invalid user ID at line {}: {}",
+                    value,
+                    index + 1,
+                    line.trim()
+                )
+            })
+        })
+        .collect()
+}
+
+fn parse_locale(value: &str) -> Result<Locale, String> {
+    Locale::try_from(value).map_err(|_| format!("unknown locale: {}", value))
+}
+
+fn parse_top(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(0) => Err("expected at least 1 account".to_string()),
+        Ok(top) => Ok(top),
+        Err(error) => Err(format!("{}: {}", value, error)),
+    }
+}
+
+fn parse_profile_image_size(value: &str) -> Result<u32, String> {
+    match value.parse() {
+        Ok(0) => Err("expected a size of at least 1 pixel".to_string()),
+        Ok(size) => Ok(size),
+        Err(error) => Err(format!("{}: {}", value, error)),
+    }
+}
+
+impl ReportOpts {
+    fn thumbnail_dir(&self, base_path: &Path) -> ThumbnailDir {
+        let thumbnails = match &self.thumbnails {
+            Some(thumbnails) => ThumbnailDir::new(thumbnails),
+            // There's no base directory to look for thumbnails in when reading stdin.
+            None if data::is_stdin(base_path) => ThumbnailDir::disabled(),
+            None => ThumbnailDir::in_base(base_path),
+        }
+        .with_size(self.profile_image_size);
+        let thumbnails = if self.index_thumbnails {
+            thumbnails.with_index()
+        } else {
+            thumbnails
+        };
+
+        match &self.placeholder {
+            Some(placeholder) => thumbnails.with_placeholder(placeholder),
+            None => thumbnails,
+        }
+    }
+
+    fn format_header(&self, date: NaiveDate) -> String {
+        if self.relative_dates && self.group_by == GroupBy::Day {
+            if let Some(relative) = format_relative_date(date, run_date()) {
+                return relative;
+            }
+        }
+
+        match self.locale {
+            Some(locale) => date
+                .format_localized(self.group_by.header_format(), locale)
+                .to_string(),
+            None => date.format(self.group_by.header_format()).to_string(),
+        }
+    }
+
+    /// Where the report is written: the index in the day directory when splitting by day (which is
+    /// created if needed), or the requested output file.
+    fn output_path(&self) -> Result<Option<String>, Error> {
+        match &self.split_by_day {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                Ok(Some(dir.join("index.md").to_string_lossy().into_owned()))
+            }
+            None => Ok(self.output.clone()),
+        }
+    }
+
+    /// Write the section for a day to the report, or to its own file when splitting by day.
+    fn write_day_section<F: FnOnce(&mut dyn Write) -> Result<(), Error>>(
+        &self,
+        out: &mut dyn Write,
+        date: NaiveDate,
+        write_section: F,
+    ) -> Result<(), Error> {
+        match &self.split_by_day {
+            Some(dir) => write_output(
+                Some(&dir.join(format!("{}.md", date)).to_string_lossy()),
+                write_section,
+            ),
+            None => write_section(out),
+        }
+    }
+
+    /// Start a collapsible block for a day's tables if requested, expanded if `open` is set.
+    fn write_details_start(
+        &self,
+        out: &mut dyn Write,
+        summary: &str,
+        open: bool,
+    ) -> Result<(), Error> {
+        if self.collapsible {
+            writeln!(
+                out,
+                "\n<details{}>\n<summary>{}</summary>",
+                if open { " open" } else { "" },
+                summary
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_details_end(&self, out: &mut dyn Write) -> Result<(), Error> {
+        if self.collapsible {
+            writeln!(out, "\n</details>")?;
+        }
+
+        Ok(())
+    }
+
+    /// The link to the section for `date` from the contents.
+    fn date_link(&self, date: NaiveDate) -> String {
+        match self.split_by_day {
+            Some(_) => format!("./{}.md", date),
+            None => self.absolute_link(&format!("#{}", self.date_anchor(date))),
+        }
+    }
+
+    /// Resolve a link relative to the report against the base URL, which is only used for the
+    /// formats that aren't read on GitHub.
+    fn absolute_link(&self, link: &str) -> String {
+        match &self.base_url {
+            Some(base_url) if matches!(self.format, Format::Html | Format::Rss) => {
+                resolve_url(base_url, link)
+            }
+            _ => link.to_string(),
+        }
+    }
+
+    /// Print the number of records that would be included for each day and in total.
+    fn write_dry_run(&self, day_counts: &[(NaiveDate, usize)]) {
+        for (date, count) in day_counts {
+            println!("{}: {}", date, self.format_count(*count));
+        }
+
+        println!(
+            "Total: {}",
+            self.format_count(day_counts.iter().map(|(_, count)| count).sum())
+        );
+    }
+
+    /// Write a footer noting when the report was generated and the latest date in the data, unless
+    /// it's disabled or only the day sections are wanted.
+    fn write_footer(
+        &self,
+        out: &mut dyn Write,
+        latest_date: Option<NaiveDate>,
+    ) -> Result<(), Error> {
+        if self.no_footer || self.quiet {
+            return Ok(());
+        }
+
+        write!(
+            out,
+            "\nGenerated on {} UTC",
+            Utc::now().format(EVENT_TIMESTAMP_FORMAT)
+        )?;
+        match latest_date {
+            Some(latest_date) => writeln!(
+                out,
+                " covering data through {}.",
+                latest_date.format(HEADER_DATE_FORMAT).to_string().trim()
+            )?,
+            None => writeln!(out, ".")?,
+        }
+
+        Ok(())
+    }
+
+    /// The section heading for `date`.
+    ///
+    /// Relative headings are given an explicit anchor with the date, so that links to them don't
+    /// change from one day to the next.
+    fn format_heading(&self, date: NaiveDate) -> String {
+        if self.relative_dates {
+            format!(
+                "<a id=\"{}\"></a>{}",
+                self.date_anchor(date),
+                self.format_header(date)
+            )
+        } else {
+            self.format_header(date)
+        }
+    }
+
+    /// The in-page anchor of the section header for `date`.
+    fn date_anchor(&self, date: NaiveDate) -> String {
+        if self.relative_dates {
+            date.format("%Y-%m-%d").to_string()
+        } else {
+            heading_anchor(&self.format_header(date))
+        }
+    }
+
+    fn thousands_separator(&self) -> Option<&'static str> {
+        self.locale
+            .map(|locale| locale_match!(locale => LC_NUMERIC::THOUSANDS_SEP))
+    }
+
+    /// Format a count for display, grouping digits only when a locale is given.
+    fn format_count(&self, count: usize) -> String {
+        match self.thousands_separator() {
+            Some(separator) => group_digits(count as u64, separator),
+            None => count.to_string(),
+        }
+    }
+
+    /// Load the records accepted by `include` on the reported days (or weeks or months) from a base
+    /// directory, most recent first.
+    fn reported_days<T: Record, P: Fn(&T) -> bool>(
+        &self,
+        base: &str,
+        include: P,
+    ) -> Result<Grouped<T>, Error> {
+        let mut skipped = 0;
+        let mut date_records = self.load(&[base.to_string()], include, false, &mut skipped)?;
+        report_skipped(skipped);
+
+        date_records.retain(|(date, _)| self.includes_date(*date));
+
+        Ok(self
+            .group_by
+            .regroup(date_records)
+            .into_iter()
+            .take(self.days)
+            .collect())
+    }
+
+    /// Load and group the records accepted by `include` from the data files for the report.
+    ///
+    /// Unless `full_history` is set, only the reported days are guaranteed to be loaded.
+    fn load<T: Record, P: Fn(&T) -> bool>(
+        &self,
+        bases: &[String],
+        include: P,
+        full_history: bool,
+        skipped: &mut usize,
+    ) -> Result<Grouped<T>, Error> {
+        let data_files = self.data_files(bases);
+        let options = self.read.options();
+
+        // Standard input can only be read once, so it can't be validated first or loaded in two
+        // passes.
+        let reads_stdin = data_files.iter().any(|path| data::is_stdin(path));
+
+        if self.validate && reads_stdin {
+            log::warn!("Data read from stdin isn't validated");
+        }
+        if self.validate {
+            let data_files = data_files
+                .iter()
+                .filter(|path| !data::is_stdin(path))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            for (path, line, problem) in data::validate::<T>(&data_files, &options, Utc::now())? {
+                log::warn!(
+                    "Implausible record at line {} of {}: {}",
+                    line,
+                    path.display(),
+                    problem
+                );
+            }
+        }
+
+        let started = Instant::now();
+        let on_invalid = skip_or_fail(self.skip_bad_rows, skipped);
+        // Records older than the maximum age are dropped even when the full history is needed.
+        let include = |record: &T| self.within_max_age(record.timestamp()) && include(record);
+        let include_reported =
+            |record: &T| self.includes_date(record.timestamp().date_naive()) && include(record);
+
+        // The loaders that only keep the reported days count dates, not longer periods.
+        let date_records = if self.group_by != GroupBy::Day {
+            let mut records = data::load_merged(&data_files, &options, on_invalid)?;
+            records.retain(|record| include(record));
+
+            data::group(records)
+        } else if self.assume_sorted {
+            data::load_sorted(
+                &data_files,
+                &options,
+                self.days,
+                self.until,
+                include_reported,
+                on_invalid,
+            )?
+        } else if full_history {
+            let mut records = data::load_merged(&data_files, &options, on_invalid)?;
+            records.retain(|record| include(record));
+
+            data::group(records)
+        } else if reads_stdin {
+            data::load_recent(
+                &data_files,
+                &options,
+                self.days,
+                include_reported,
+                on_invalid,
+            )?
+        } else {
+            data::load_two_pass(
+                &data_files,
+                &options,
+                self.days,
+                include_reported,
+                on_invalid,
+            )?
+        };
+
+        log::info!(
+            "Loaded {} records on {} dates from {} data files in {:.2?} ({} rows skipped)",
+            date_records
+                .iter()
+                .map(|(_, records)| records.len())
+                .sum::<usize>(),
+            date_records.len(),
+            data_files.len(),
+            started.elapsed(),
+            skipped
+        );
+
+        Ok(date_records)
+    }
+
+    fn data_files(&self, bases: &[String]) -> Vec<PathBuf> {
+        match &self.file {
+            Some(file) => vec![PathBuf::from(file)],
+            None => bases
+                .iter()
+                .map(|base| data::data_file(Path::new(base), self.prefer_compressed))
+                .collect(),
+        }
+    }
+
+    /// Put the reported days in the requested order.
+    ///
+    /// Days are loaded with the most recent first, so for ascending order the most recent `days`
+    /// are selected before they're reversed.
+    fn sort_days<T>(&self, date_records: &mut Vec<T>) {
+        if self.sort == SortOrder::Asc {
+            date_records.truncate(self.days);
+            date_records.reverse();
+        }
+    }
+
+    /// Sort the records for a day by impact when requested (they're already sorted by follower
+    /// count otherwise).
+    ///
+    /// Ties keep their order by follower count.
+    fn sort_records<A: Account>(&self, records: &mut [A]) {
+        if self.sort_by == SortBy::Impact {
+            records.sort_by(|a, b| self.impact(b).total_cmp(&self.impact(a)));
+        }
+    }
+
+    /// The impact score of an account: its follower count, multiplied by the verified multiplier
+    /// if it's verified.
+    fn impact<A: Account>(&self, record: &A) -> f64 {
+        let followers_count = record.followers_count() as f64;
+
+        if record.verified() {
+            followers_count * self.verified_multiplier
+        } else {
+            followers_count
+        }
+    }
+
+    /// Whether the user is included, and the account matches the requested statuses and screen name
+    /// pattern.
+    fn includes_account<A: Account>(&self, record: &A) -> bool {
+        self.includes_user(record.user_id())
+            && (!self.verified_only || record.verified())
+            && (!self.protected_only || record.protected())
+            && self
+                .screen_name_regex
+                .as_ref()
+                .is_none_or(|pattern| record.matches_screen_name(pattern))
+    }
+
+    /// Whether the user isn't listed in the exclude file, and is in the watchlist if there is one.
+    fn includes_user(&self, user_id: u64) -> bool {
+        self.excluded_user_ids
+            .as_ref()
+            .is_none_or(|user_ids| !user_ids.contains(&user_id))
+            && self
+                .watched_user_ids
+                .as_ref()
+                .is_none_or(|user_ids| user_ids.contains(&user_id))
+    }
+
+    /// A description of the reported period, such as "the last 7 days", for the introduction.
+    fn reported_period(&self) -> String {
+        match self.days {
+            1 => format!("the last {}", self.group_by.unit()),
+            days => format!("the last {} {}s", days, self.group_by.unit()),
+        }
+    }
+
+    /// A description of the users the report covers, for the introduction.
+    fn covered_users(&self, min_followers: usize) -> String {
+        if self.watched_user_ids.is_some() {
+            "the users on a watchlist".to_string()
+        } else {
+            format!("all users with more than {} followers", min_followers)
+        }
+    }
+
+    /// The follower threshold for the report, which watchlists ignore.
+    fn min_followers(&self, min_followers: usize) -> usize {
+        if self.watched_user_ids.is_some() {
+            0
+        } else {
+            min_followers
+        }
+    }
+
+    /// The number of rows to display in a table with the given number of reported records.
+    fn displayed_row_count(&self, reported_count: usize) -> usize {
+        self.max_rows_per_day
+            .map_or(reported_count, |max_rows| reported_count.min(max_rows))
+    }
+
+    /// The number of rows to display for each day, given the number of reported records for
+    /// each (in report order).
+    ///
+    /// The total row limit is allocated to the most recent days first, and days left without any
+    /// of it are omitted entirely (`None`).
+    fn displayed_row_counts(&self, reported_counts: &[usize]) -> Vec<Option<usize>> {
+        let mut displayed_counts = vec![None; reported_counts.len()];
+        let mut remaining = self.limit_total;
+
+        let newest_first: Box<dyn Iterator<Item = usize>> = if self.sort == SortOrder::Asc {
+            Box::new((0..reported_counts.len()).rev())
+        } else {
+            Box::new(0..reported_counts.len())
+        };
+
+        for index in newest_first {
+            if remaining == Some(0) {
+                break;
+            }
+
+            let mut displayed_count = self.displayed_row_count(reported_counts[index]);
+            if let Some(remaining) = &mut remaining {
+                displayed_count = displayed_count.min(*remaining);
+                *remaining -= displayed_count;
+            }
+            displayed_counts[index] = Some(displayed_count);
+        }
+
+        displayed_counts
+    }
+
+    /// Whether tables are written as Markdown pipe tables rather than HTML.
+    fn pipe_tables(&self) -> bool {
+        self.format == Format::MarkdownTable
+    }
+
+    /// Redaction and top lists are only supported for the table formats, and splitting by day
+    /// only for the Markdown ones.
+    fn check_format(&self) -> Result<(), Error> {
+        match self.format {
+            Format::Markdown | Format::MarkdownTable => Ok(()),
+            Format::Html if self.split_by_day.is_none() => Ok(()),
+            _ if !self.redact && self.top.is_none() && self.split_by_day.is_none() => Ok(()),
+            other => Err(Error::UnsupportedFormat(
+                other
+                    .to_possible_value()
+                    .map_or("unknown", |value| value.get_name()),
+            )),
+        }
+    }
+
+    /// The (one-based) position of the follower count column in the tables, if it's shown.
+    ///
+    /// Top lists have an extra detection date column at the start, and there may also be a rank
+    /// column.
+    fn followers_column<C: PartialEq>(&self, columns: &[C], followers: &C) -> Option<usize> {
+        columns
+            .iter()
+            .position(|column| column == followers)
+            .map(|index| index + 1 + usize::from(self.top.is_some()) + usize::from(self.show_rank))
+    }
+
+    /// The width to fit the plain text tables in.
+    fn text_width(&self) -> usize {
+        self.width
+            .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+            .unwrap_or(DEFAULT_TEXT_WIDTH)
+    }
+
+    /// Whether to use ANSI codes in the plain text format.
+    fn use_color(&self) -> bool {
+        match self.color {
+            ColorMode::Auto => self.output.is_none() && std::io::stdout().is_terminal(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+
+    /// Whether a record detected at the given time is recent enough to be loaded.
+    fn within_max_age(&self, timestamp: DateTime<Utc>) -> bool {
+        self.max_age_days
+            .is_none_or(|days| timestamp.date_naive() >= run_date() - Duration::days(days.into()))
+    }
+
+    /// Whether the given date falls within the requested date range.
+    fn includes_date(&self, date: NaiveDate) -> bool {
+        self.since.is_none_or(|since| date >= since) && self.until.is_none_or(|until| date <= until)
+    }
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    ScreenNames,
+    Suspensions,
+}
+
+impl Kind {
+    fn default_base(self) -> &'static str {
+        match self {
+            Kind::ScreenNames => "screen-names/",
+            Kind::Suspensions => "suspensions/",
+        }
+    }
+
+    /// The report's default minimum follower count for this kind of data.
+    fn default_min_followers(self) -> usize {
+        match self {
+            Kind::ScreenNames => 200,
+            Kind::Suspensions => 200,
+        }
+    }
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum GroupBy {
+    Day,
+    /// ISO week
+    Week,
+    Month,
+}
+
+impl GroupBy {
+    fn unit(self) -> &'static str {
+        match self {
+            GroupBy::Day => "day",
+            GroupBy::Week => "week",
+            GroupBy::Month => "month",
+        }
+    }
+
+    fn header_format(self) -> &'static str {
+        match self {
+            GroupBy::Day => HEADER_DATE_FORMAT,
+            GroupBy::Week => HEADER_WEEK_FORMAT,
+            GroupBy::Month => HEADER_MONTH_FORMAT,
+        }
+    }
+
+    /// The first day of the period containing the given date.
+    fn period_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            GroupBy::Day => date,
+            GroupBy::Week => date - Duration::days(date.weekday().num_days_from_monday().into()),
+            GroupBy::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Merge days into periods, which are identified by their first day.
+    ///
+    /// The days must be sorted with the most recent first, and the records for each period are
+    /// re-sorted by their sort key.
+    fn regroup<T: Record>(self, date_records: Grouped<T>) -> Grouped<T> {
+        if self == GroupBy::Day {
+            return date_records;
+        }
+
+        let mut period_records: Grouped<T> = vec![];
+
+        for (date, records) in date_records {
+            let start = self.period_start(date);
+
+            match period_records.last_mut() {
+                Some((last_start, last_records)) if *last_start == start => {
+                    last_records.extend(records)
+                }
+                _ => period_records.push((start, records)),
+            }
+        }
+
+        for (_, records) in &mut period_records {
+            records.sort_by_cached_key(|record| record.sort_key());
+        }
+
+        period_records
+    }
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum SortOrder {
+    /// Oldest first
+    Asc,
+    /// Newest first
+    Desc,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum SortBy {
+    /// Most followers first
+    Followers,
+    /// Highest impact score first (the follower count, multiplied by --verified-multiplier for
+    /// verified accounts)
+    Impact,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum ColorMode {
+    /// Only when writing to a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    Markdown,
+    /// Markdown with pipe tables instead of HTML ones
+    #[clap(alias = "md")]
+    MarkdownTable,
+    Json,
+    /// RSS 2.0 feed (screen names only)
+    Rss,
+    /// The reported records in the data file format
+    Csv,
+    /// Standalone HTML document
+    Html,
+    /// CSV counts of all the records per day in each follower count range, ignoring the follower
+    /// threshold (see --buckets)
+    Buckets,
+    /// Plain text for reading in a terminal
+    Text,
+}
+
+impl Format {
+    /// The file extension for reports in this format.
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown | Format::MarkdownTable => "md",
+            Format::Json => "json",
+            Format::Rss => "xml",
+            Format::Csv | Format::Buckets => "csv",
+            Format::Html => "html",
+            Format::Text => "txt",
+        }
+    }
 }
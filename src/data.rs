@@ -0,0 +1,531 @@
+use super::{Error, SCREEN_NAMES_FIELD_COUNT, SUSPENSIONS_FIELD_COUNT};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use csv::StringRecord;
+use flate2::bufread::MultiGzDecoder;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+pub const DATA_FILE_NAME: &str = "data.csv";
+pub const COMPRESSED_DATA_FILE_NAME: &str = "data.csv.gz";
+/// The base directory or data file path that stands for standard input.
+pub const STDIN_PATH: &str = "-";
+
+/// How many rows are read between progress messages.
+pub const PROGRESS_INTERVAL: u64 = 100_000;
+
+/// How the data files are read.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ReadOptions {
+    /// The byte that separates fields.
+    pub delimiter: u8,
+    /// Whether the first row of each file is a header to skip.
+    ///
+    /// This is the default, but note that the published `data.csv` dumps don't have header rows.
+    pub has_headers: bool,
+    /// Report the number of rows read every [`PROGRESS_INTERVAL`] rows while loading.
+    pub show_progress: bool,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        ReadOptions {
+            delimiter: b',',
+            has_headers: true,
+            show_progress: false,
+        }
+    }
+}
+
+/// Records grouped by the date they were detected.
+pub type Grouped<T> = Vec<(NaiveDate, Vec<T>)>;
+
+/// The known layouts of the `data.csv` files, which are told apart by their number of fields.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Schema {
+    ScreenNames,
+    Suspensions,
+}
+
+impl Schema {
+    /// The schema of a data file whose rows have the given number of fields, if it's a known one.
+    pub fn detect(field_count: usize) -> Option<Self> {
+        match field_count {
+            SCREEN_NAMES_FIELD_COUNT => Some(Self::ScreenNames),
+            SUSPENSIONS_FIELD_COUNT => Some(Self::Suspensions),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ScreenNames => write!(f, "screen names"),
+            Self::Suspensions => write!(f, "suspensions"),
+        }
+    }
+}
+
+/// A row parsed from one of the `data.csv` files.
+pub trait Record: TryFrom<StringRecord, Error = Error> {
+    type SortKey: Ord;
+
+    /// The layout of the data files containing these records.
+    const SCHEMA: Schema;
+
+    /// The time the event was detected.
+    fn timestamp(&self) -> DateTime<Utc>;
+
+    /// The key used to order records within a single day.
+    ///
+    /// This should only tie for identical rows, so that reports don't depend on the input order.
+    fn sort_key(&self) -> Self::SortKey;
+
+    /// Describe any implausible values in the record, which usually indicate misordered columns.
+    fn problems(&self, now: DateTime<Utc>) -> Vec<String>;
+}
+
+/// Whether a base directory or data file path stands for standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new(STDIN_PATH)
+}
+
+/// Find the data file in the given directory.
+///
+/// The uncompressed `data.csv` is used if it exists, unless `prefer_compressed` is set and there
+/// is also a `data.csv.gz`. A base of [`STDIN_PATH`] is kept as it is, to read standard input.
+pub fn data_file(base: &Path, prefer_compressed: bool) -> PathBuf {
+    if is_stdin(base) {
+        return base.to_path_buf();
+    }
+
+    let path = base.join(DATA_FILE_NAME);
+    let compressed_path = base.join(COMPRESSED_DATA_FILE_NAME);
+
+    if compressed_path.exists() && (prefer_compressed || !path.exists()) {
+        compressed_path
+    } else {
+        path
+    }
+}
+
+/// Open a data file, decompressing it if it has a `.gz` extension.
+///
+/// [`STDIN_PATH`] reads (uncompressed) data from standard input, which can only be done once.
+///
+/// The reader is flexible about record lengths so that rows with the wrong number of fields are
+/// reported by the record parsers (with their line numbers) instead of as generic CSV errors.
+/// The delimiter and header handling are taken from `options`.
+pub fn open_data(path: &Path, options: &ReadOptions) -> Result<csv::Reader<Box<dyn Read>>, Error> {
+    log::debug!("Opening {}", path.display());
+
+    if is_stdin(path) {
+        return Ok(csv_reader(Box::new(std::io::stdin()), options));
+    }
+
+    let file = File::open(path).map_err(|error| match error.kind() {
+        ErrorKind::NotFound => Error::DataFileNotFound(path.to_path_buf()),
+        _ => Error::Io(error),
+    })?;
+    let reader: Box<dyn Read> = if path.extension() == Some(OsStr::new("gz")) {
+        Box::new(MultiGzDecoder::new(BufReader::new(file)))
+    } else {
+        Box::new(file)
+    };
+
+    Ok(csv_reader(reader, options))
+}
+
+fn csv_reader(reader: Box<dyn Read>, options: &ReadOptions) -> csv::Reader<Box<dyn Read>> {
+    csv::ReaderBuilder::new()
+        .flexible(true)
+        .delimiter(options.delimiter)
+        .has_headers(options.has_headers)
+        .from_reader(reader)
+}
+
+/// Iterate over the rows of a data file, reporting progress if it has been enabled.
+///
+/// The schema is detected from the first row, which fails with a single error for the whole file
+/// if it isn't the one for `T`, instead of an error for every row.
+fn rows<'a, T: Record>(
+    data: &'a mut csv::Reader<Box<dyn Read>>,
+    path: &'a Path,
+    options: &ReadOptions,
+) -> impl Iterator<Item = Result<StringRecord, Error>> + 'a {
+    let show_progress = options.show_progress;
+
+    data.records().zip(1..).map(move |(result, count)| {
+        if show_progress && count % PROGRESS_INTERVAL == 0 {
+            log::info!("Read {} rows from {}", count, path.display());
+        }
+
+        let row = result?;
+
+        if count == 1 {
+            check_schema::<T>(&row, path)?;
+        }
+
+        Ok(row)
+    })
+}
+
+fn check_schema<T: Record>(row: &StringRecord, path: &Path) -> Result<(), Error> {
+    match Schema::detect(row.len()) {
+        Some(schema) if schema == T::SCHEMA => Ok(()),
+        Some(schema) => Err(Error::SchemaMismatch {
+            path: path.to_path_buf(),
+            expected: T::SCHEMA,
+            found: schema,
+        }),
+        None => Err(Error::UnknownSchema {
+            path: path.to_path_buf(),
+            field_count: row.len(),
+        }),
+    }
+}
+
+/// The line of the data file a record was read from (or zero if unknown).
+pub fn record_line(record: &StringRecord) -> u64 {
+    record.position().map_or(0, |position| position.line())
+}
+
+/// Parse a timestamp given in seconds since the epoch.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    value
+        .parse::<i64>()
+        .ok()
+        .and_then(|timestamp_s| Utc.timestamp_opt(timestamp_s, 0).single())
+}
+
+/// Twitter's launch (21 March 2006), before which no account was created or event detected.
+pub const TWITTER_LAUNCH_TIMESTAMP_S: i64 = 1_142_899_200;
+
+/// Far more followers than any account has ever had.
+pub const MAX_PLAUSIBLE_FOLLOWERS: usize = 1_000_000_000;
+
+/// Describe what is implausible about a timestamp field, if anything.
+pub fn timestamp_problem(field: &str, value: DateTime<Utc>, now: DateTime<Utc>) -> Option<String> {
+    if value.timestamp() < TWITTER_LAUNCH_TIMESTAMP_S {
+        Some(format!("{} is before Twitter launched", field))
+    } else if value > now {
+        Some(format!("{} is in the future", field))
+    } else {
+        None
+    }
+}
+
+/// Check every record in the given data files for implausible values.
+///
+/// The result contains the path and line of each problem found, together with its description.
+/// Rows that can't be parsed at all are left for the loaders to report.
+pub fn validate<T: Record>(
+    paths: &[PathBuf],
+    options: &ReadOptions,
+    now: DateTime<Utc>,
+) -> Result<Vec<(PathBuf, u64, String)>, Error> {
+    let mut problems = vec![];
+
+    for path in paths {
+        let mut data = open_data(path, options)?;
+
+        for result in rows::<T>(&mut data, path, options) {
+            let row = result?;
+            let line = record_line(&row);
+
+            if let Ok(record) = T::try_from(row) {
+                for problem in record.problems(now) {
+                    problems.push((path.clone(), line, problem));
+                }
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Load all records from the given data file.
+///
+/// Records that fail to parse are passed to `on_invalid`, which can either return the error to
+/// abort loading or return `Ok(())` to skip the row.
+pub fn load<T: Record, F: FnMut(Error) -> Result<(), Error>>(
+    path: &Path,
+    options: &ReadOptions,
+    mut on_invalid: F,
+) -> Result<Vec<T>, Error> {
+    let mut data = open_data(path, options)?;
+    let mut records = vec![];
+
+    for result in rows::<T>(&mut data, path, options) {
+        match T::try_from(result?) {
+            Ok(record) => records.push(record),
+            Err(error) => on_invalid(error)?,
+        }
+    }
+
+    Ok(records)
+}
+
+/// Load and merge the records from several data files.
+///
+/// Rows that appear identically in more than one file (or more than once in the same file) are
+/// only included once.
+pub fn load_merged<T: Record, F: FnMut(Error) -> Result<(), Error>>(
+    paths: &[PathBuf],
+    options: &ReadOptions,
+    mut on_invalid: F,
+) -> Result<Vec<T>, Error> {
+    let mut seen = HashSet::new();
+    let mut records = vec![];
+
+    for path in paths {
+        let mut data = open_data(path, options)?;
+
+        for result in rows::<T>(&mut data, path, options) {
+            let row = result?;
+
+            if seen.insert(row.iter().map(str::to_string).collect::<Vec<_>>()) {
+                match T::try_from(row) {
+                    Ok(record) => records.push(record),
+                    Err(error) => on_invalid(error)?,
+                }
+            }
+        }
+    }
+
+    Ok(records)
+}
+
+/// Load and merge the records for the most recent `days` dates from several data files, reading
+/// each file once.
+///
+/// Only records accepted by `include` are kept, and only for the most recent dates seen so far,
+/// so memory use is bounded by the size of the report instead of the size of the data files.
+/// This is for data that can only be read once (such as standard input), since otherwise
+/// [`load_two_pass`] only keeps the records that end up in the report. Rows are deduplicated as
+/// in [`load_merged`], and the result is ordered as in [`group`].
+pub fn load_recent<T: Record, P: Fn(&T) -> bool, F: FnMut(Error) -> Result<(), Error>>(
+    paths: &[PathBuf],
+    options: &ReadOptions,
+    days: usize,
+    include: P,
+    mut on_invalid: F,
+) -> Result<Grouped<T>, Error> {
+    let mut by_date: BTreeMap<NaiveDate, (Vec<T>, HashSet<Vec<String>>)> = BTreeMap::new();
+
+    for path in paths {
+        let mut data = open_data(path, options)?;
+
+        for result in rows::<T>(&mut data, path, options) {
+            let row = result?;
+            let fields = row.iter().map(str::to_string).collect::<Vec<_>>();
+
+            let record = match T::try_from(row) {
+                Ok(record) => record,
+                Err(error) => {
+                    on_invalid(error)?;
+                    continue;
+                }
+            };
+
+            let date = record.timestamp().date_naive();
+
+            if !include(&record)
+                || (by_date.len() >= days
+                    && by_date.keys().next().is_none_or(|oldest| date < *oldest))
+            {
+                continue;
+            }
+
+            let (records, seen) = by_date.entry(date).or_default();
+
+            if seen.insert(fields) {
+                records.push(record);
+            }
+
+            if by_date.len() > days {
+                by_date.pop_first();
+            }
+        }
+    }
+
+    Ok(group(
+        by_date
+            .into_values()
+            .flat_map(|(records, _)| records)
+            .collect(),
+    ))
+}
+
+/// Load and merge the records for the most recent `days` dates from several data files, reading
+/// each file twice.
+///
+/// The first pass only finds the dates with records accepted by `include`, and the second
+/// collects the records on the most recent `days` of them, so memory use is bounded by the size
+/// of the report (and the number of dates) instead of the size of the data files. Invalid rows are
+/// only passed to `on_invalid` during the first pass. Rows are deduplicated as in
+/// [`load_merged`], and the result is ordered as in [`group`].
+pub fn load_two_pass<T: Record, P: Fn(&T) -> bool, F: FnMut(Error) -> Result<(), Error>>(
+    paths: &[PathBuf],
+    options: &ReadOptions,
+    days: usize,
+    include: P,
+    mut on_invalid: F,
+) -> Result<Grouped<T>, Error> {
+    let mut dates = HashSet::new();
+
+    for path in paths {
+        let mut data = open_data(path, options)?;
+
+        for result in rows::<T>(&mut data, path, options) {
+            match T::try_from(result?) {
+                Ok(record) => {
+                    if include(&record) {
+                        dates.insert(record.timestamp().date_naive());
+                    }
+                }
+                Err(error) => on_invalid(error)?,
+            }
+        }
+    }
+
+    let mut dates = dates.into_iter().collect::<Vec<_>>();
+    dates.sort_unstable_by_key(|date| Reverse(*date));
+    dates.truncate(days);
+    let dates = dates.into_iter().collect::<HashSet<_>>();
+
+    let mut seen = HashSet::new();
+    let mut records = vec![];
+
+    for path in paths {
+        let mut data = open_data(path, options)?;
+
+        for result in rows::<T>(&mut data, path, options) {
+            let row = result?;
+            let fields = row.iter().map(str::to_string).collect::<Vec<_>>();
+
+            if let Ok(record) = T::try_from(row) {
+                if dates.contains(&record.timestamp().date_naive())
+                    && include(&record)
+                    && seen.insert(fields)
+                {
+                    records.push(record);
+                }
+            }
+        }
+    }
+
+    Ok(group(records))
+}
+
+/// Load and merge the records for the most recent `days` dates from data files whose rows are in
+/// timestamp order, reading each file once and only as far as `until` (if given).
+///
+/// Only the most recent dates seen so far are kept while reading, so rows that are out of order
+/// and older than all of the kept dates are dropped. Rows are deduplicated as in [`load_merged`],
+/// and the result is ordered as in [`group`].
+pub fn load_sorted<T: Record, P: Fn(&T) -> bool, F: FnMut(Error) -> Result<(), Error>>(
+    paths: &[PathBuf],
+    options: &ReadOptions,
+    days: usize,
+    until: Option<NaiveDate>,
+    include: P,
+    mut on_invalid: F,
+) -> Result<Grouped<T>, Error> {
+    let mut window: VecDeque<(NaiveDate, Vec<T>, HashSet<Vec<String>>)> = VecDeque::new();
+
+    for path in paths {
+        let mut data = open_data(path, options)?;
+
+        for result in rows::<T>(&mut data, path, options) {
+            let row = result?;
+            let fields = row.iter().map(str::to_string).collect::<Vec<_>>();
+
+            let record = match T::try_from(row) {
+                Ok(record) => record,
+                Err(error) => {
+                    on_invalid(error)?;
+                    continue;
+                }
+            };
+
+            let date = record.timestamp().date_naive();
+
+            // The rest of the file is after the window.
+            if until.is_some_and(|until| date > until) {
+                break;
+            }
+            if !include(&record) {
+                continue;
+            }
+
+            let index = match window.iter().position(|(kept, _, _)| *kept == date) {
+                Some(index) => index,
+                None if days > 0 && window.back().is_none_or(|(newest, _, _)| date > *newest) => {
+                    window.push_back((date, vec![], HashSet::new()));
+                    if window.len() > days {
+                        window.pop_front();
+                    }
+
+                    window.len() - 1
+                }
+                None => continue,
+            };
+
+            let (_, records, seen) = &mut window[index];
+
+            if seen.insert(fields) {
+                records.push(record);
+            }
+        }
+    }
+
+    Ok(group(
+        window
+            .into_iter()
+            .flat_map(|(_, records, _)| records)
+            .collect(),
+    ))
+}
+
+/// Load all records and group them by date.
+///
+/// See [`group`] for the ordering of the result.
+pub fn load_grouped<T: Record, F: FnMut(Error) -> Result<(), Error>>(
+    path: &Path,
+    options: &ReadOptions,
+    on_invalid: F,
+) -> Result<Grouped<T>, Error> {
+    Ok(group(load(path, options, on_invalid)?))
+}
+
+/// Group records by date.
+///
+/// Dates are sorted with the most recent first, and the records for each date are sorted by their
+/// sort key.
+pub fn group<T: Record>(records: Vec<T>) -> Grouped<T> {
+    let mut by_date: HashMap<NaiveDate, Vec<T>> = HashMap::new();
+
+    for record in records {
+        let records = by_date.entry(record.timestamp().date_naive()).or_default();
+        records.push(record);
+    }
+
+    let mut date_records = by_date
+        .into_iter()
+        .map(|(date, mut records)| {
+            records.sort_by_cached_key(|record| record.sort_key());
+            (date, records)
+        })
+        .collect::<Vec<_>>();
+
+    date_records.sort_by_key(|(date, _)| Reverse(*date));
+
+    date_records
+}
@@ -0,0 +1,54 @@
+pub mod data;
+pub mod records;
+pub mod text;
+pub mod thumbnails;
+
+pub use records::{ScreenNameRecord, SuspensionRecord};
+
+pub const SCREEN_NAMES_FIELD_COUNT: usize = 8;
+pub const SUSPENSIONS_FIELD_COUNT: usize = 9;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("CSV error")]
+    Csv(#[from] csv::Error),
+    #[error("JSON error")]
+    Json(#[from] serde_json::Error),
+    #[error("Data file not found: {}", .0.display())]
+    DataFileNotFound(std::path::PathBuf),
+    #[error("Unsupported format for this report: {0}")]
+    UnsupportedFormat(&'static str),
+    #[error("Unsupported option for this command: {0}")]
+    UnsupportedOption(&'static str),
+    #[error("Invalid screen names record at line {line}: {}", describe_invalid_record(.record, SCREEN_NAMES_FIELD_COUNT))]
+    InvalidScreenNamesRecord {
+        line: u64,
+        record: csv::StringRecord,
+    },
+    #[error("Invalid suspensions record at line {line}: {}", describe_invalid_record(.record, SUSPENSIONS_FIELD_COUNT))]
+    InvalidSuspensionsRecord {
+        line: u64,
+        record: csv::StringRecord,
+    },
+    #[error("Unknown data file schema in {}: found {field_count} fields, expected {SCREEN_NAMES_FIELD_COUNT} for screen names or {SUSPENSIONS_FIELD_COUNT} for suspensions", .path.display())]
+    UnknownSchema {
+        path: std::path::PathBuf,
+        field_count: usize,
+    },
+    #[error("Data file {} contains {found} data, not {expected} data", .path.display())]
+    SchemaMismatch {
+        path: std::path::PathBuf,
+        expected: data::Schema,
+        found: data::Schema,
+    },
+}
+
+fn describe_invalid_record(record: &csv::StringRecord, expected: usize) -> String {
+    if record.len() == expected {
+        "invalid field value".to_string()
+    } else {
+        format!("expected {} fields, found {}", expected, record.len())
+    }
+}
@@ -0,0 +1,186 @@
+use super::data::{
+    parse_timestamp, record_line, timestamp_problem, Record, Schema, MAX_PLAUSIBLE_FOLLOWERS,
+};
+use super::{Error, SCREEN_NAMES_FIELD_COUNT, SUSPENSIONS_FIELD_COUNT};
+use chrono::{DateTime, Utc};
+use csv::StringRecord;
+use std::cmp::Reverse;
+use std::convert::TryFrom;
+
+/// A screen name change detected for an account.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ScreenNameRecord {
+    pub timestamp: DateTime<Utc>,
+    pub user_id: u64,
+    pub verified: bool,
+    pub protected: bool,
+    pub followers_count: usize,
+    pub previous_screen_name: String,
+    pub new_screen_name: String,
+    pub profile_image_url: String,
+}
+
+impl TryFrom<StringRecord> for ScreenNameRecord {
+    type Error = Error;
+
+    fn try_from(value: StringRecord) -> Result<Self, Self::Error> {
+        if value.len() == SCREEN_NAMES_FIELD_COUNT {
+            let ((((timestamp, user_id), verified), protected), followers_count) =
+                parse_timestamp(&value[0])
+                    .zip(value[1].parse::<u64>().ok())
+                    .zip(value[2].parse::<bool>().ok())
+                    .zip(value[3].parse::<bool>().ok())
+                    .zip(value[4].parse::<usize>().ok())
+                    .ok_or_else(|| Error::InvalidScreenNamesRecord {
+                        line: record_line(&value),
+                        record: value.clone(),
+                    })?;
+
+            Ok(Self {
+                timestamp,
+                user_id,
+                verified,
+                protected,
+                followers_count,
+                previous_screen_name: value[5].to_string(),
+                new_screen_name: value[6].to_string(),
+                profile_image_url: value[7].to_string(),
+            })
+        } else {
+            Err(Error::InvalidScreenNamesRecord {
+                line: record_line(&value),
+                record: value,
+            })
+        }
+    }
+}
+
+impl Record for ScreenNameRecord {
+    type SortKey = (Reverse<usize>, u64, DateTime<Utc>, String);
+    const SCHEMA: Schema = Schema::ScreenNames;
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn sort_key(&self) -> Self::SortKey {
+        (
+            Reverse(self.followers_count),
+            self.user_id,
+            self.timestamp,
+            self.new_screen_name.clone(),
+        )
+    }
+
+    fn problems(&self, now: DateTime<Utc>) -> Vec<String> {
+        timestamp_problem("timestamp", self.timestamp, now)
+            .into_iter()
+            .chain(account_problems(self.user_id, self.followers_count))
+            .collect()
+    }
+}
+
+/// A suspension detected for an account, with the time it was reversed, if it has been.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SuspensionRecord {
+    pub timestamp: DateTime<Utc>,
+    pub reversal: Option<DateTime<Utc>>,
+    pub user_id: u64,
+    pub created_at: DateTime<Utc>,
+    pub screen_name: String,
+    pub verified: bool,
+    pub protected: bool,
+    pub followers_count: usize,
+    pub profile_image_url: String,
+}
+
+impl TryFrom<StringRecord> for SuspensionRecord {
+    type Error = Error;
+
+    fn try_from(value: StringRecord) -> Result<Self, Self::Error> {
+        if value.len() == SUSPENSIONS_FIELD_COUNT {
+            let (
+                (((((timestamp, reversal), user_id), created_at), verified), protected),
+                followers_count,
+            ) = parse_timestamp(&value[0])
+                .zip(if value[1].trim().is_empty() {
+                    Some(None)
+                } else {
+                    parse_timestamp(value[1].trim()).map(Some)
+                })
+                .zip(value[2].parse::<u64>().ok())
+                .zip(parse_timestamp(&value[3]))
+                .zip(value[5].parse::<bool>().ok())
+                .zip(value[6].parse::<bool>().ok())
+                .zip(value[7].parse::<usize>().ok())
+                .ok_or_else(|| Error::InvalidSuspensionsRecord {
+                    line: record_line(&value),
+                    record: value.clone(),
+                })?;
+
+            Ok(Self {
+                timestamp,
+                reversal,
+                user_id,
+                created_at,
+                screen_name: value[4].to_string(),
+                verified,
+                protected,
+                followers_count,
+                profile_image_url: value[8].to_string(),
+            })
+        } else {
+            Err(Error::InvalidSuspensionsRecord {
+                line: record_line(&value),
+                record: value,
+            })
+        }
+    }
+}
+
+impl Record for SuspensionRecord {
+    type SortKey = (Reverse<usize>, u64, DateTime<Utc>, String);
+    const SCHEMA: Schema = Schema::Suspensions;
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn sort_key(&self) -> Self::SortKey {
+        (
+            Reverse(self.followers_count),
+            self.user_id,
+            self.timestamp,
+            self.screen_name.clone(),
+        )
+    }
+
+    fn problems(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut problems = vec![];
+        problems.extend(timestamp_problem("timestamp", self.timestamp, now));
+        if let Some(reversal) = self.reversal {
+            problems.extend(timestamp_problem("reversal", reversal, now));
+            if reversal < self.timestamp {
+                problems.push("reversal is before the suspension".to_string());
+            }
+        }
+        problems.extend(timestamp_problem("created_at", self.created_at, now));
+        problems.extend(account_problems(self.user_id, self.followers_count));
+        problems
+    }
+}
+
+/// Describe any implausible account details.
+fn account_problems(user_id: u64, followers_count: usize) -> Vec<String> {
+    let mut problems = vec![];
+    if user_id == 0 {
+        problems.push("user_id is zero".to_string());
+    }
+    if followers_count > MAX_PLAUSIBLE_FOLLOWERS {
+        problems.push(format!(
+            "followers_count {} is implausibly large",
+            followers_count
+        ));
+    }
+    problems
+}
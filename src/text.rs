@@ -0,0 +1,48 @@
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Truncate a string to at most `max_len` user-perceived characters, adding an ellipsis if
+/// anything was removed.
+///
+/// Lengths are counted in grapheme clusters, so emoji and other multi-codepoint characters are
+/// never split.
+pub fn truncate_graphemes(value: &str, max_len: usize) -> Cow<'_, str> {
+    match value.grapheme_indices(true).nth(max_len) {
+        Some((index, _)) => Cow::Owned(format!("{}…", &value[..index])),
+        None => Cow::Borrowed(value),
+    }
+}
+
+/// The number of user-perceived characters (grapheme clusters) in a string.
+pub fn grapheme_len(value: &str) -> usize {
+    value.graphemes(true).count()
+}
+
+/// Truncate a string to fit in `width` user-perceived characters, including the ellipsis added
+/// if anything was removed.
+pub fn fit_graphemes(value: &str, width: usize) -> Cow<'_, str> {
+    if grapheme_len(value) > width {
+        truncate_graphemes(value, width.saturating_sub(1))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Build the anchor GitHub generates for a Markdown heading with the given text.
+///
+/// Letters are lowercased, punctuation (including whitespace other than spaces) is removed and
+/// each space becomes a hyphen, so several spaces in a row become several hyphens. Leading and
+/// trailing whitespace isn't part of a heading's text, so padded date fields like `%e` don't
+/// produce leading hyphens.
+pub fn heading_anchor(heading: &str) -> String {
+    heading
+        .trim()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
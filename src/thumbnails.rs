@@ -0,0 +1,199 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The default thumbnail size, which matches Twitter's largest profile image variant.
+pub const DEFAULT_SIZE: u32 = 400;
+
+/// A directory of local profile image thumbnails.
+pub struct ThumbnailDir {
+    /// The directory to check for thumbnail files.
+    path: PathBuf,
+    /// The prefix used for thumbnail links in the report.
+    link_prefix: String,
+    /// The width and height of the thumbnails in pixels.
+    size: u32,
+    /// A local image to use for profile image URLs that can't have thumbnails.
+    placeholder: Option<String>,
+    /// The names of the files in the directory, if it has been scanned up front.
+    index: Option<HashSet<String>>,
+}
+
+impl ThumbnailDir {
+    /// The `thumbnails` directory next to the report, linked relatively.
+    pub fn in_base(base_path: &Path) -> Self {
+        Self {
+            path: base_path.join("thumbnails"),
+            link_prefix: "./thumbnails".to_string(),
+            size: DEFAULT_SIZE,
+            placeholder: None,
+            index: None,
+        }
+    }
+
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: PathBuf::from(path),
+            link_prefix: path.trim_end_matches('/').to_string(),
+            size: DEFAULT_SIZE,
+            placeholder: None,
+            index: None,
+        }
+    }
+
+    /// No thumbnails at all, so that the original profile image URLs are used.
+    pub fn disabled() -> Self {
+        Self {
+            path: PathBuf::new(),
+            link_prefix: String::new(),
+            size: DEFAULT_SIZE,
+            placeholder: None,
+            index: Some(HashSet::new()),
+        }
+    }
+
+    /// Use thumbnails of the given size instead of the default 400x400.
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Use the image at the given path for profile image URLs that aren't in the usual form (such
+    /// as default avatars), if it exists.
+    pub fn with_placeholder(mut self, path: &str) -> Self {
+        self.placeholder = Path::new(path).exists().then(|| path.to_string());
+        self
+    }
+
+    /// List the files in the directory once, so that thumbnails are looked up in memory instead of
+    /// checking the file system for each one (which is slow on network file systems).
+    ///
+    /// A directory that can't be read is treated as empty.
+    pub fn with_index(mut self) -> Self {
+        let index = std::fs::read_dir(&self.path)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect();
+
+        self.index = Some(index);
+        self
+    }
+
+    fn contains(&self, file_name: &str) -> bool {
+        match &self.index {
+            Some(index) => index.contains(file_name),
+            None => self.path.join(file_name).exists(),
+        }
+    }
+}
+
+fn profile_image_url_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^https?://([^/]+)/profile_images/(\d+)/(.*?)(?:_normal|_mini|_bigger|_200x200|_400x400)?(\.[a-zA-Z0-9-]+)?$",
+        )
+        .unwrap()
+    })
+}
+
+/// The local thumbnail file name for a profile image URL.
+enum ThumbnailFileName {
+    Name(String),
+    /// A profile image URL without an extension, whose thumbnail can't be named.
+    MissingExtension,
+    /// Not a profile image URL (such as a default avatar).
+    Unmatched,
+}
+
+/// The name of the local thumbnail file of the given size for a profile image URL, if it can have
+/// one.
+///
+/// Profile image URLs may have any of the `_normal`, `_mini`, `_bigger`, `_200x200`, or `_400x400`
+/// size suffixes (or none, for the original upload), and all have the same thumbnails.
+fn thumbnail_file_name(profile_image_url: &str, size: u32) -> ThumbnailFileName {
+    match profile_image_url_re().captures(profile_image_url) {
+        Some(captures) => match captures.get(4) {
+            Some(extension) => ThumbnailFileName::Name(format!(
+                "{}-{}_{}x{}{}",
+                &captures[2],
+                &captures[3],
+                size,
+                size,
+                extension.as_str()
+            )),
+            None => ThumbnailFileName::MissingExtension,
+        },
+        None => ThumbnailFileName::Unmatched,
+    }
+}
+
+/// Find the local thumbnail for a profile image URL, or return the original URL if there isn't one.
+///
+/// Thumbnails are resolved to the directory's size (`_400x400` by default), whatever the size of
+/// the original URL. URLs that can't have thumbnails (such as default avatars) use the directory's
+/// placeholder image if it has one, but profile image URLs without an extension keep the original
+/// URL, since they're still images of the account.
+pub fn make_profile_image_thumbnail_url(
+    profile_image_url: &str,
+    thumbnails: &ThumbnailDir,
+) -> String {
+    match thumbnail_file_name(profile_image_url, thumbnails.size) {
+        ThumbnailFileName::Name(file_name) if thumbnails.contains(&file_name) => {
+            format!("{}/{}", thumbnails.link_prefix, file_name)
+        }
+        ThumbnailFileName::Name(_) => profile_image_url.to_string(),
+        ThumbnailFileName::MissingExtension => {
+            log::debug!(
+                "Profile image URL without an extension: {}",
+                profile_image_url
+            );
+            profile_image_url.to_string()
+        }
+        ThumbnailFileName::Unmatched => thumbnails
+            .placeholder
+            .clone()
+            .unwrap_or_else(|| profile_image_url.to_string()),
+    }
+}
+
+/// The path where the local thumbnail for a profile image URL would be, if it can have one.
+pub fn profile_image_thumbnail_path(
+    profile_image_url: &str,
+    thumbnails: &ThumbnailDir,
+) -> Option<PathBuf> {
+    match thumbnail_file_name(profile_image_url, thumbnails.size) {
+        ThumbnailFileName::Name(file_name) => Some(thumbnails.path.join(file_name)),
+        ThumbnailFileName::MissingExtension | ThumbnailFileName::Unmatched => None,
+    }
+}
+
+/// Point a profile image URL at another host (such as a mirror), keeping the rest of the URL.
+///
+/// URLs that don't look like profile image URLs are returned unchanged.
+pub fn rewrite_profile_image_host<'a>(profile_image_url: &'a str, host: &str) -> Cow<'a, str> {
+    match profile_image_url_re()
+        .captures(profile_image_url)
+        .and_then(|captures| captures.get(1))
+    {
+        Some(current_host) => Cow::Owned(format!(
+            "{}{}{}",
+            &profile_image_url[..current_host.start()],
+            host,
+            &profile_image_url[current_host.end()..]
+        )),
+        None => Cow::Borrowed(profile_image_url),
+    }
+}
+
+/// Use HTTPS for a profile image URL, so that it isn't blocked as mixed content on HTTPS pages.
+pub fn https_profile_image_url(profile_image_url: &str) -> Cow<'_, str> {
+    match profile_image_url.strip_prefix("http://") {
+        Some(rest) => Cow::Owned(format!("https://{}", rest)),
+        None => Cow::Borrowed(profile_image_url),
+    }
+}
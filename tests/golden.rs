@@ -0,0 +1,44 @@
+//! Compare report output for the fixture data with the golden files in `tests/golden/`.
+//!
+//! Run with `UPDATE_GOLDEN=1` to regenerate the golden files after an intended change.
+
+use std::path::Path;
+use std::process::Command;
+
+fn check_golden(args: &[&str], golden_file: &str) {
+    // The footer includes the current time.
+    let output = Command::new(env!("CARGO_BIN_EXE_report"))
+        .args(args)
+        .arg("--no-footer")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let golden_path = Path::new("tests/golden").join(golden_file);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&golden_path, actual).unwrap();
+    } else {
+        let expected = std::fs::read_to_string(&golden_path).unwrap();
+
+        assert_eq!(actual, expected, "output differs from {:?}", golden_path);
+    }
+}
+
+#[test]
+fn screen_names_markdown() {
+    check_golden(
+        &["screen-names", "--base", "tests/fixtures/screen-names/"],
+        "screen-names.md",
+    );
+}
+
+#[test]
+fn suspensions_markdown() {
+    check_golden(
+        &["suspensions", "--base", "tests/fixtures/suspensions/"],
+        "suspensions.md",
+    );
+}
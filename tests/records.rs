@@ -0,0 +1,55 @@
+use csv::StringRecord;
+use std::convert::TryFrom;
+use twitter_watch::{Error, SuspensionRecord};
+
+fn suspension_row(reversal: &str) -> StringRecord {
+    StringRecord::from(vec![
+        "1648800000",
+        reversal,
+        "2001",
+        "1300000000",
+        "suspended_one",
+        "false",
+        "false",
+        "10000",
+        "https://pbs.twimg.com/profile_images/1500000000000000011/AbCdEf12_normal.jpg",
+    ])
+}
+
+#[test]
+fn suspension_whitespace_reversal_is_not_reversed() {
+    let record = SuspensionRecord::try_from(suspension_row("  ")).unwrap();
+
+    assert_eq!(record.reversal, None);
+}
+
+#[test]
+fn suspension_padded_reversal_is_parsed() {
+    let record = SuspensionRecord::try_from(suspension_row(" 1649000000 ")).unwrap();
+
+    assert_eq!(
+        record.reversal.map(|value| value.timestamp()),
+        Some(1649000000)
+    );
+}
+
+#[test]
+fn suspension_garbage_reversal_is_invalid_suspensions_record() {
+    let error = SuspensionRecord::try_from(suspension_row("yesterday")).unwrap_err();
+
+    assert!(matches!(error, Error::InvalidSuspensionsRecord { .. }));
+}
+
+#[test]
+fn malformed_suspension_is_invalid_suspensions_record() {
+    let mut fields = suspension_row("")
+        .iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    fields[7] = "many".to_string();
+    assert_eq!(fields.len(), 9);
+
+    let error = SuspensionRecord::try_from(StringRecord::from(fields)).unwrap_err();
+
+    assert!(matches!(error, Error::InvalidSuspensionsRecord { .. }));
+}
@@ -0,0 +1,672 @@
+use std::process::{Command, Output};
+
+fn report(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_report"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn suspensions_malformed_timestamp() {
+    let output = report(&[
+        "suspensions",
+        "--base",
+        "tests/fixtures/suspensions-malformed-timestamp/",
+    ]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: Invalid suspensions record at line 3: invalid field value\n"
+    );
+}
+
+#[test]
+fn screen_names_unknown_schema() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names-unknown-schema/",
+        "--skip-bad-rows",
+    ]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: Unknown data file schema in tests/fixtures/screen-names-unknown-schema/data.csv: found 10 fields, expected 8 for screen names or 9 for suspensions\n"
+    );
+}
+
+#[test]
+fn suspensions_skip_bad_rows() {
+    let output = report(&[
+        "suspensions",
+        "--base",
+        "tests/fixtures/suspensions-malformed-timestamp/",
+        "--skip-bad-rows",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: Invalid suspensions record at line 3: invalid field value\nWarning: Skipped 1 malformed rows\n"
+    );
+}
+
+#[test]
+fn screen_names_markup_is_escaped() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names-markup/",
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("<td>&lt;b&gt;accel&lt;/b&gt;</td>"));
+    assert!(stdout.contains("<a href=\"https://twitter.com/x&quot;&amp;y\">x&quot;&amp;y</a>"));
+    assert!(!stdout.contains("<b>"));
+}
+
+#[test]
+fn screen_names_emoji_are_preserved() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names-emoji/",
+        "--format",
+        "html",
+        "--max-name-len",
+        "3",
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Joined emoji sequences and combining characters aren't split by truncation.
+    assert!(stdout.contains("<td>caf…</td>"));
+    assert!(stdout.contains("<a href=\"https://twitter.com/👨‍👩‍👧&lt;3🏳️‍🌈\">👨‍👩‍👧&lt;3…</a>"));
+    assert!(!stdout.contains("<3"));
+}
+
+#[test]
+fn screen_names_empty() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--min-followers",
+        "1000000000",
+        "--no-footer",
+    ]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "No changes detected in the selected window.\n"
+    );
+}
+
+#[test]
+fn screen_names_headerless() {
+    let skipped = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names-headerless/",
+        "--format",
+        "csv",
+    ]);
+    let included = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names-headerless/",
+        "--format",
+        "csv",
+        "--has-header",
+        "false",
+    ]);
+
+    assert!(skipped.status.success());
+    assert!(included.status.success());
+
+    let skipped = String::from_utf8(skipped.stdout).unwrap();
+    let included = String::from_utf8(included.stdout).unwrap();
+
+    assert!(!skipped.contains("alpha_new"));
+    assert!(skipped.contains("beta_new"));
+    assert!(included.contains("alpha_new"));
+    assert!(included.contains("beta_new"));
+}
+
+#[test]
+fn search_headerless() {
+    let skipped = report(&[
+        "search",
+        "--base",
+        "tests/fixtures/screen-names-headerless/",
+        "1001",
+    ]);
+    let included = report(&[
+        "search",
+        "--base",
+        "tests/fixtures/screen-names-headerless/",
+        "--has-header",
+        "false",
+        "1001",
+    ]);
+
+    assert!(skipped.status.success());
+    assert!(included.status.success());
+
+    assert!(!String::from_utf8(skipped.stdout)
+        .unwrap()
+        .contains("alpha_new"));
+    assert!(String::from_utf8(included.stdout)
+        .unwrap()
+        .contains("alpha_new"));
+}
+
+#[test]
+fn search_unknown_suspension() {
+    let output = report(&[
+        "search",
+        "--kind",
+        "suspensions",
+        "--base",
+        "tests/fixtures/suspensions/",
+        "2007",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2022-04-02 11:00:00: suspended without account details, reversed 2022-04-03 12:46:40\n"
+    );
+}
+
+#[test]
+fn screen_names_empty_report_exit_code() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--min-followers",
+        "100000000",
+    ]);
+
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn screen_names_exclude_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let exclude_file = dir.path().join("exclude.txt");
+    std::fs::write(&exclude_file, "1001\n\n").unwrap();
+
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--exclude-file",
+        exclude_file.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!stdout.contains("alpha_new"));
+    assert!(stdout.contains("beta_new"));
+}
+
+#[test]
+fn screen_names_exclude_file_invalid_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let exclude_file = dir.path().join("exclude.txt");
+    std::fs::write(&exclude_file, "1001\nalpha\n").unwrap();
+
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--exclude-file",
+        exclude_file.to_str().unwrap(),
+    ]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("invalid user ID at line 2: alpha"));
+}
+
+#[test]
+fn screen_names_watchlist_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let watchlist_file = dir.path().join("watchlist.txt");
+    std::fs::write(&watchlist_file, "1003\n").unwrap();
+
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--watchlist-file",
+        watchlist_file.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // The watched account is reported despite being below the follower threshold.
+    assert!(stdout.contains("gamma_new"));
+    assert!(!stdout.contains("alpha_new"));
+}
+
+#[test]
+fn screen_names_watchlist_and_exclude_file_conflict() {
+    let dir = tempfile::tempdir().unwrap();
+    let user_id_file = dir.path().join("user-ids.txt");
+    std::fs::write(&user_id_file, "1001\n").unwrap();
+    let user_id_file = user_id_file.to_str().unwrap();
+
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--watchlist-file",
+        user_id_file,
+        "--exclude-file",
+        user_id_file,
+    ]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("cannot be used with"));
+}
+
+#[test]
+fn screen_names_pipe_tables() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names-markup/",
+        "--format",
+        "md",
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!stdout.contains("<table>"));
+    assert!(stdout.contains("| --- | --- | --- | --- | :---: | --- |\n"));
+    assert!(stdout
+        .contains(" | \\<b\\>accel\\<\\/b\\> | [x\\\"\\&y](https://twitter.com/x\\\"\\&y) | "));
+    // Pipes, parentheses, and spaces in image URLs would break the links or the table.
+    assert!(stdout.contains("[![](https://pbs.twimg.com/profile_images/1373110414754471941/MG%7Cw%29AE%20VU6_normal.jpg)]("));
+}
+
+#[test]
+fn suspensions_split_by_day() {
+    let dir = tempfile::tempdir().unwrap();
+    let split_dir = dir.path().join("days");
+
+    let output = report(&[
+        "suspensions",
+        "--base",
+        "tests/fixtures/suspensions/",
+        "--split-by-day",
+        split_dir.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let index = std::fs::read_to_string(split_dir.join("index.md")).unwrap();
+    let day = std::fs::read_to_string(split_dir.join("2022-04-01.md")).unwrap();
+
+    assert!(index.contains("(./2022-04-01.md)"));
+    assert!(!index.contains("<table>"));
+    assert!(day.starts_with("\n##  1 April 2022\n"));
+    assert!(day.contains("suspended_one"));
+}
+
+#[test]
+fn screen_names_from_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_report"))
+        .args(["screen-names", "--base", "-", "--format", "csv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(&std::fs::read("tests/fixtures/screen-names/data.csv").unwrap())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(
+            report(&[
+                "screen-names",
+                "--base",
+                "tests/fixtures/screen-names/",
+                "--format",
+                "csv"
+            ])
+            .stdout
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn dashboard_split_by_day_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_dir = dir.path().join("out");
+    let split_dir = dir.path().join("days");
+
+    let output = report(&[
+        "dashboard",
+        "--screen-names-base",
+        "tests/fixtures/screen-names/",
+        "--suspensions-base",
+        "tests/fixtures/suspensions/",
+        "--out-dir",
+        out_dir.to_str().unwrap(),
+        "--split-by-day",
+        split_dir.to_str().unwrap(),
+    ]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: Unsupported option for this command: --split-by-day\n"
+    );
+    assert!(!out_dir.exists());
+    assert!(!split_dir.exists());
+}
+
+#[test]
+fn screen_names_intro_describes_reported_period() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--group-by",
+        "week",
+        "--days",
+        "4",
+    ]);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("This page presents the last 4 weeks of available data for all users"));
+}
+
+#[test]
+fn suspensions_count_min_followers() {
+    let output = report(&[
+        "suspensions",
+        "--base",
+        "tests/fixtures/suspensions/",
+        "--format",
+        "md",
+        "--table-min-followers",
+        "1000",
+        "--count-min-followers",
+        "100",
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Only the account with 10,000 followers is in the tables, but all three with account details
+    // are counted for its day.
+    assert!(stdout.contains("Found 4 suspensions, with 3 included here."));
+    assert!(stdout.contains("suspended\\_one"));
+    assert!(!stdout.contains("reversed\\_two"));
+}
+
+#[test]
+fn suspensions_only_unknown_shown() {
+    let output = report(&[
+        "suspensions",
+        "--base",
+        "tests/fixtures/suspensions/",
+        "--format",
+        "md",
+        "--since",
+        "2022-04-02",
+        "--min-followers",
+        "1000",
+        "--show-unknown",
+    ]);
+
+    // Only the unknown suspensions are in the tables, so the report isn't empty.
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!stdout.contains("No suspensions detected"));
+    assert!(stdout.contains("2006"));
+}
+
+#[test]
+fn buckets_ignore_min_followers() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--format",
+        "buckets",
+        "--min-followers",
+        "1000",
+    ]);
+
+    assert!(output.status.success());
+
+    // The accounts below the table threshold are still counted in the lowest range.
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "date,0-999,1000-9999,10000-99999,100000+\n\
+         2022-04-03,2,0,0,0\n\
+         2022-04-02,0,1,0,1\n\
+         2022-04-01,2,1,0,0\n"
+    );
+}
+
+#[test]
+fn suspensions_spikes_before_since() {
+    let output = report(&[
+        "suspensions",
+        "--base",
+        "tests/fixtures/suspensions/",
+        "--spike-threshold",
+        "0.5",
+        "--since",
+        "2022-04-02",
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // The day before --since is still used as the baseline, and the marker is kept out of the
+    // header so that the contents link matches its anchor.
+    assert!(stdout.contains("* [ 2 April 2022 (3 suspensions found) 📈](#2-april-2022)\n"));
+    assert!(stdout.contains("\n##  2 April 2022\n📈 Found 3 suspensions"));
+}
+
+#[test]
+fn screen_names_assume_sorted() {
+    let csv = |args: &[&str]| {
+        let output = report(
+            &[
+                &[
+                    "screen-names",
+                    "--base",
+                    "tests/fixtures/screen-names/",
+                    "--format",
+                    "csv",
+                ],
+                args,
+            ]
+            .concat(),
+        );
+        assert!(output.status.success());
+
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(csv(&["--assume-sorted"]), csv(&[]));
+    assert_eq!(
+        csv(&["--assume-sorted", "--until", "2022-04-01"]),
+        csv(&["--until", "2022-04-01"])
+    );
+}
+
+#[test]
+fn check_thumbnails_uses_report_options() {
+    let output = report(&[
+        "check-thumbnails",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--profile-image-size",
+        "200",
+        "--verified-only",
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("/1500000000000000002-GhIjKl34_200x200.jpg "));
+    assert!(!stdout.contains("_400x400"));
+    assert!(!stdout.contains("1500000000000000001"));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: 2 of 2 thumbnails missing\n"
+    );
+}
+
+#[test]
+fn profile_image_size_keeps_display_size() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--profile-image-size",
+        "200",
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("width=\"40px\" height=\"40px\""));
+    assert!(!stdout.contains("width=\"20px\""));
+}
+
+#[test]
+fn profile_image_size_zero_is_rejected() {
+    let output = report(&[
+        "screen-names",
+        "--base",
+        "tests/fixtures/screen-names/",
+        "--profile-image-size",
+        "0",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("--profile-image-size"));
+}
+
+#[test]
+fn top_zero_is_rejected() {
+    let output = report(&[
+        "suspensions",
+        "--base",
+        "tests/fixtures/suspensions/",
+        "--top",
+        "0",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("--top"));
+}
+
+#[test]
+fn dashboard() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_dir = dir.path().join("out");
+
+    let output = report(&[
+        "dashboard",
+        "--screen-names-base",
+        "tests/fixtures/screen-names/",
+        "--suspensions-base",
+        "tests/fixtures/suspensions/",
+        "--out-dir",
+        out_dir.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+
+    let index = std::fs::read_to_string(out_dir.join("index.md")).unwrap();
+    let suspensions = std::fs::read_to_string(out_dir.join("suspensions.md")).unwrap();
+
+    assert!(index.contains("* [Screen name changes](./screen-names.md): 5 changes"));
+    assert!(index.contains("* [Suspensions](./suspensions.md): 4 suspensions"));
+    // The reports use the same defaults as their subcommands.
+    assert!(suspensions.contains("<td>—</td>"));
+    assert!(out_dir.join("screen-names.md").exists());
+}
+
+#[test]
+fn suspensions_account_age_months() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_report"))
+        .args(["suspensions", "--base", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Created 362 days before the suspension, which is less than a year.
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"timestamp,reversal,user_id,created_at,screen_name,verified,protected,followers_count,profile_image_url\n1648800000,,2001,1617494400,almost_a_year,false,false,10000,https://pbs.twimg.com/profile_images/1500000000000000011/AbCdEf12_normal.jpg\n")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("<td>11mo</td>"));
+}
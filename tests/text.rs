@@ -0,0 +1,64 @@
+use twitter_watch::text::{fit_graphemes, heading_anchor, truncate_graphemes};
+
+#[test]
+fn short_names_are_unchanged() {
+    assert_eq!(truncate_graphemes("accel_capital", 30), "accel_capital");
+    assert_eq!(truncate_graphemes("abc", 3), "abc");
+}
+
+#[test]
+fn long_names_are_truncated_with_ellipsis() {
+    assert_eq!(truncate_graphemes("abcdefgh", 3), "abc…");
+}
+
+#[test]
+fn multibyte_characters_are_counted_once() {
+    assert_eq!(truncate_graphemes("ÄÖÜäöü", 6), "ÄÖÜäöü");
+    assert_eq!(truncate_graphemes("日本語のなまえ", 3), "日本語…");
+}
+
+#[test]
+fn names_are_fitted_including_the_ellipsis() {
+    assert_eq!(fit_graphemes("abcd", 4), "abcd");
+    assert_eq!(fit_graphemes("abcde", 4), "abc…");
+    assert_eq!(fit_graphemes("🇺🇸🇺🇸🇺🇸", 2), "🇺🇸…");
+}
+
+#[test]
+fn emoji_are_not_split() {
+    // A family emoji made of several code points joined with zero-width joiners.
+    let family = "👨\u{200d}👩\u{200d}👧";
+
+    assert_eq!(
+        truncate_graphemes(&format!("a{}{}b", family, family), 2),
+        format!("a{}…", family)
+    );
+    // A flag is two regional indicator code points.
+    assert_eq!(truncate_graphemes("🇺🇸🇺🇸🇺🇸", 1), "🇺🇸…");
+}
+
+#[test]
+fn combining_marks_stay_attached() {
+    assert_eq!(
+        truncate_graphemes("e\u{301}e\u{301}e\u{301}", 2),
+        "e\u{301}e\u{301}…"
+    );
+}
+
+#[test]
+fn heading_anchors_match_github() {
+    assert_eq!(heading_anchor(" 1 May 2023"), "1-may-2023");
+    assert_eq!(heading_anchor("12 December 2022"), "12-december-2022");
+    assert_eq!(heading_anchor("Week 05 of 2023"), "week-05-of-2023");
+    assert_eq!(heading_anchor("1. März 2023"), "1-märz-2023");
+}
+
+#[test]
+fn heading_anchors_keep_inner_spaces() {
+    // `%e` pads single-digit days with a space, which isn't part of the heading text.
+    assert_eq!(heading_anchor(" 1 May 2023"), "1-may-2023");
+    // Each space inside the heading becomes a hyphen, as on GitHub.
+    assert_eq!(heading_anchor(" 1 tháng  5 2023"), "1-tháng--5-2023");
+    // Other whitespace is removed like punctuation.
+    assert_eq!(heading_anchor("1\u{a0}de maio de 2023"), "1de-maio-de-2023");
+}
@@ -0,0 +1,250 @@
+use std::fs::File;
+use twitter_watch::thumbnails::{
+    https_profile_image_url, make_profile_image_thumbnail_url, ThumbnailDir,
+};
+
+fn thumbnail_dir(file_names: &[&str]) -> (tempfile::TempDir, ThumbnailDir) {
+    let base = tempfile::tempdir().unwrap();
+    let path = base.path().join("thumbnails");
+    std::fs::create_dir(&path).unwrap();
+
+    for file_name in file_names {
+        File::create(path.join(file_name)).unwrap();
+    }
+
+    let thumbnails = ThumbnailDir::in_base(base.path());
+
+    (base, thumbnails)
+}
+
+#[test]
+fn normal_jpg() {
+    let (_base, thumbnails) = thumbnail_dir(&["1507536514963501056-r_SX0T33_400x400.jpg"]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.jpg",
+            &thumbnails
+        ),
+        "./thumbnails/1507536514963501056-r_SX0T33_400x400.jpg"
+    );
+}
+
+#[test]
+fn normal_webp() {
+    let (_base, thumbnails) = thumbnail_dir(&["1507536514963501056-r_SX0T33_400x400.webp"]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.webp",
+            &thumbnails
+        ),
+        "./thumbnails/1507536514963501056-r_SX0T33_400x400.webp"
+    );
+}
+
+#[test]
+fn bigger_png() {
+    let (_base, thumbnails) = thumbnail_dir(&["1138130482464514048-QPWF-Gwe_400x400.png"]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1138130482464514048/QPWF-Gwe_bigger.png",
+            &thumbnails
+        ),
+        "./thumbnails/1138130482464514048-QPWF-Gwe_400x400.png"
+    );
+}
+
+#[test]
+fn without_size_suffix() {
+    let (_base, thumbnails) = thumbnail_dir(&["1491163543504314372-VmeUehsM_400x400.jpg"]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1491163543504314372/VmeUehsM.jpg",
+            &thumbnails
+        ),
+        "./thumbnails/1491163543504314372-VmeUehsM_400x400.jpg"
+    );
+}
+
+#[test]
+fn mini_jpg() {
+    let (_base, thumbnails) = thumbnail_dir(&["1507536514963501056-r_SX0T33_400x400.jpg"]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_mini.jpg",
+            &thumbnails
+        ),
+        "./thumbnails/1507536514963501056-r_SX0T33_400x400.jpg"
+    );
+}
+
+#[test]
+fn size_200x200_jpg() {
+    let (_base, thumbnails) = thumbnail_dir(&["1507536514963501056-r_SX0T33_400x400.jpg"]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_200x200.jpg",
+            &thumbnails
+        ),
+        "./thumbnails/1507536514963501056-r_SX0T33_400x400.jpg"
+    );
+}
+
+#[test]
+fn size_400x400_jpg() {
+    let (_base, thumbnails) = thumbnail_dir(&["1507536514963501056-r_SX0T33_400x400.jpg"]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_400x400.jpg",
+            &thumbnails
+        ),
+        "./thumbnails/1507536514963501056-r_SX0T33_400x400.jpg"
+    );
+}
+
+#[test]
+fn indexed() {
+    let (base, thumbnails) = thumbnail_dir(&["1507536514963501056-r_SX0T33_400x400.jpg"]);
+    let thumbnails = thumbnails.with_index();
+
+    // Files added after the directory was indexed aren't found.
+    File::create(
+        base.path()
+            .join("thumbnails/1491163543504314372-VmeUehsM_400x400.jpg"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.jpg",
+            &thumbnails
+        ),
+        "./thumbnails/1507536514963501056-r_SX0T33_400x400.jpg"
+    );
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1491163543504314372/VmeUehsM.jpg",
+            &thumbnails
+        ),
+        "https://pbs.twimg.com/profile_images/1491163543504314372/VmeUehsM.jpg"
+    );
+}
+
+#[test]
+fn missing_thumbnail() {
+    let (_base, thumbnails) = thumbnail_dir(&[]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_mini.jpg",
+            &thumbnails
+        ),
+        "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_mini.jpg"
+    );
+}
+
+#[test]
+fn unmatched_url() {
+    let (_base, thumbnails) = thumbnail_dir(&[]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://abs.twimg.com/sticky/default_profile_images/default_profile_normal.png",
+            &thumbnails
+        ),
+        "https://abs.twimg.com/sticky/default_profile_images/default_profile_normal.png"
+    );
+}
+
+#[test]
+fn missing_extension() {
+    // Thumbnails are only found for URLs with an extension, even if a file without one exists.
+    let (_base, thumbnails) = thumbnail_dir(&["1507536514963501056-r_SX0T33_400x400"]);
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal",
+            &thumbnails
+        ),
+        "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal"
+    );
+}
+
+#[test]
+fn missing_extension_with_placeholder() {
+    // URLs without an extension are still profile images, so they don't use the placeholder.
+    let (base, thumbnails) = thumbnail_dir(&["placeholder.png"]);
+    let placeholder = base.path().join("thumbnails/placeholder.png");
+    let thumbnails = thumbnails.with_placeholder(placeholder.to_str().unwrap());
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal",
+            &thumbnails
+        ),
+        "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal"
+    );
+}
+
+#[test]
+fn http_to_https() {
+    assert_eq!(
+        https_profile_image_url(
+            "http://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.jpg"
+        ),
+        "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.jpg"
+    );
+    assert_eq!(
+        https_profile_image_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.jpg"
+        ),
+        "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.jpg"
+    );
+}
+
+#[test]
+fn unmatched_with_placeholder() {
+    let (base, thumbnails) = thumbnail_dir(&["placeholder.png"]);
+    let placeholder = base.path().join("thumbnails/placeholder.png");
+    let thumbnails = thumbnails.with_placeholder(placeholder.to_str().unwrap());
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://abs.twimg.com/sticky/default_profile_images/default_profile_normal.png",
+            &thumbnails
+        ),
+        placeholder.to_str().unwrap()
+    );
+}
+
+#[test]
+fn unmatched_with_missing_placeholder() {
+    let (base, thumbnails) = thumbnail_dir(&[]);
+    let placeholder = base.path().join("thumbnails/placeholder.png");
+    let thumbnails = thumbnails.with_placeholder(placeholder.to_str().unwrap());
+
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://abs.twimg.com/sticky/default_profile_images/default_profile_normal.png",
+            &thumbnails
+        ),
+        "https://abs.twimg.com/sticky/default_profile_images/default_profile_normal.png"
+    );
+}
+
+#[test]
+fn disabled() {
+    assert_eq!(
+        make_profile_image_thumbnail_url(
+            "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.jpg",
+            &ThumbnailDir::disabled().with_index()
+        ),
+        "https://pbs.twimg.com/profile_images/1507536514963501056/r_SX0T33_normal.jpg"
+    );
+}